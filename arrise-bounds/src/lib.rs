@@ -0,0 +1,271 @@
+//! The `#[bounds]` attribute, re-exported as `arrise::bounds`.
+//!
+//! Generic code over arrise types has to spell out a `[(); <T as SerialSize>::SIZE]:` bound
+//! for every buffer it names, as well as the bounds of every compound impl it goes through.
+//! This attribute derives them from the signature instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::ToTokens;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{
+    parse_quote, GenericArgument, Generics, Item, PathArguments, Token, Type,
+    TypeParamBound, TypePath, WherePredicate,
+};
+
+/// Add the const well-formedness bounds required by `generic_const_exprs`
+/// to a function or an impl block.
+///
+/// Bounds are added for every type parameter and every where clause type
+/// bounded by `SerialSize`, `Serialize` or `Deserialize`,
+/// and for every type passed as an argument:
+///
+/// ```ignore
+/// #[arrise::bounds(Option<(T, u8)>)]
+/// fn frame<T: Serialize>(value: T) -> [u8; <Option<(T, u8)> as SerialSize>::SIZE] {
+///     Some((value, 0)).to_bytes()
+/// }
+/// ```
+///
+/// For tuples, arrays, `Option`, `Bound`, ranges, smart pointers, cells and locks,
+/// the bounds of their own impls are added too, recursively.
+#[proc_macro_attribute]
+pub fn bounds(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The traits whose implementors name buffers of their `SIZE`.
+const TRAITS: [&str; 3] = ["SerialSize", "Serialize", "Deserialize"];
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut types: Vec<Type> = Punctuated::<Type, Token![,]>::parse_terminated
+        .parse2(attr)?
+        .into_iter()
+        .collect();
+    let mut item: Item = syn::parse2(item)?;
+    let generics = match &mut item {
+        | Item::Fn(item) => &mut item.sig.generics,
+        | Item::Impl(item) => &mut item.generics,
+        | item => {
+            return Err(syn::Error::new_spanned(
+                item,
+                "expected a function or an impl block",
+            ))
+        }
+    };
+
+    types.extend(bounded_types(generics));
+    let mut predicates = Vec::new();
+    for ty in &types {
+        size_predicates(ty, &mut predicates);
+    }
+
+    let where_clause = generics.make_where_clause();
+    for predicate in predicates {
+        let tokens = predicate.to_token_stream().to_string();
+        if !where_clause
+            .predicates
+            .iter()
+            .any(|existing| existing.to_token_stream().to_string() == tokens)
+        {
+            where_clause.predicates.push(predicate);
+        }
+    }
+
+    Ok(item.into_token_stream())
+}
+
+/// The type parameters and where clause types bounded by one of [`TRAITS`].
+fn bounded_types(generics: &Generics) -> Vec<Type> {
+    let params = generics
+        .type_params()
+        .filter(|param| is_bounded(&param.bounds))
+        .map(|param| {
+            let ident = &param.ident;
+            parse_quote!(#ident)
+        });
+    let predicates = generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| &where_clause.predicates)
+        .filter_map(|predicate| match predicate {
+            | WherePredicate::Type(predicate) if is_bounded(&predicate.bounds) => {
+                Some(predicate.bounded_ty.clone())
+            }
+            | _ => None,
+        });
+
+    params.chain(predicates).collect()
+}
+
+fn is_bounded(bounds: &Punctuated<TypeParamBound, Token![+]>) -> bool {
+    bounds.iter().any(|bound| match bound {
+        | TypeParamBound::Trait(bound) => bound
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| TRAITS.iter().any(|name| segment.ident == name)),
+        | _ => false,
+    })
+}
+
+/// Push the bounds of `ty` and, if it is a known compound type, those of its impls.
+fn size_predicates(ty: &Type, into: &mut Vec<WherePredicate>) {
+    let ty = match ty {
+        | Type::Group(group) => return size_predicates(&group.elem, into),
+        | Type::Paren(paren) => return size_predicates(&paren.elem, into),
+        | ty => ty,
+    };
+
+    into.push(parse_quote!([(); <#ty as ::arrise::SerialSize>::SIZE]:));
+    match ty {
+        | Type::Array(array) => size_predicates(&array.elem, into),
+        | Type::Tuple(tuple) => {
+            let fields: Vec<&Type> = tuple.elems.iter().collect();
+            remainder(ty, &fields, into);
+            for field in fields {
+                size_predicates(field, into);
+            }
+        }
+        | Type::Path(path) => {
+            let Some((name, inner)) = wrapper(path) else {
+                return;
+            };
+            let bool: Type = parse_quote!(bool);
+            let u8: Type = parse_quote!(u8);
+            match name.as_str() {
+                | "Option" => {
+                    remainder(ty, &[&bool], into);
+                    remainder(ty, &[&bool, inner], into);
+                }
+                | "Bound" => {
+                    remainder(ty, &[&u8], into);
+                    remainder(ty, &[&u8, inner], into);
+                }
+                | "Range" | "RangeInclusive" => {
+                    let pair: Type = parse_quote!((#inner, #inner));
+                    remainder(ty, &[inner], into);
+                    remainder(ty, &[inner, inner], into);
+                    remainder(ty, &[&pair], into);
+                    size_predicates(&pair, into);
+                }
+                | "RangeToInclusive" | "Ordered" | "Box" | "Rc" | "Arc" | "Cell"
+                | "RefCell" | "Mutex" | "RwLock" | "Key" => remainder(ty, &[inner], into),
+                | _ => return,
+            }
+            size_predicates(inner, into);
+        }
+        | _ => {}
+    }
+}
+
+/// Push the bound on the size of `ty` left after subtracting the sizes of `parts`.
+fn remainder(ty: &Type, parts: &[&Type], into: &mut Vec<WherePredicate>) {
+    into.push(parse_quote!(
+        [(); <#ty as ::arrise::SerialSize>::SIZE #(- <#parts as ::arrise::SerialSize>::SIZE)*]:
+    ));
+}
+
+/// The name and the type argument of a path type with a single type argument.
+fn wrapper(path: &TypePath) -> Option<(String, &Type)> {
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.iter().collect::<Vec<_>>().as_slice() {
+        | [GenericArgument::Type(inner)] => Some((segment.ident.to_string(), inner)),
+        | _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+    use quote::{quote, ToTokens};
+    use syn::{parse_quote, ItemFn, WherePredicate};
+
+    fn predicates(attr: proc_macro2::TokenStream, item: ItemFn) -> Vec<String> {
+        let item: ItemFn =
+            syn::parse2(expand(attr, item.into_token_stream()).unwrap()).unwrap();
+        let where_clause = item.sig.generics.where_clause.unwrap();
+        expected(&where_clause.predicates.into_iter().collect::<Vec<_>>())
+    }
+
+    fn expected(predicates: &[WherePredicate]) -> Vec<String> {
+        predicates
+            .iter()
+            .map(|predicate| predicate.to_token_stream().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_params() {
+        let item = parse_quote! {
+            fn f<T: Serialize, U: Clone, V>(t: T, u: U, v: V) where V: arrise::Deserialize {}
+        };
+        assert_eq!(
+            expected(&[
+                parse_quote!(V: arrise::Deserialize),
+                parse_quote!([(); <T as ::arrise::SerialSize>::SIZE]:),
+                parse_quote!([(); <V as ::arrise::SerialSize>::SIZE]:),
+            ]),
+            predicates(quote!(), item)
+        );
+    }
+
+    #[test]
+    fn test_compound() {
+        let item = parse_quote! {
+            fn f<T: Serialize>(t: T) {}
+        };
+        assert_eq!(
+            expected(&[
+                parse_quote!([(); <Option<(T, u8)> as ::arrise::SerialSize>::SIZE]:),
+                parse_quote!(
+                    [(); <Option<(T, u8)> as ::arrise::SerialSize>::SIZE
+                        - <bool as ::arrise::SerialSize>::SIZE]:
+                ),
+                parse_quote!(
+                    [(); <Option<(T, u8)> as ::arrise::SerialSize>::SIZE
+                        - <bool as ::arrise::SerialSize>::SIZE
+                        - <(T, u8) as ::arrise::SerialSize>::SIZE]:
+                ),
+                parse_quote!([(); <(T, u8) as ::arrise::SerialSize>::SIZE]:),
+                parse_quote!(
+                    [(); <(T, u8) as ::arrise::SerialSize>::SIZE
+                        - <T as ::arrise::SerialSize>::SIZE
+                        - <u8 as ::arrise::SerialSize>::SIZE]:
+                ),
+                parse_quote!([(); <T as ::arrise::SerialSize>::SIZE]:),
+                parse_quote!([(); <u8 as ::arrise::SerialSize>::SIZE]:),
+                parse_quote!([(); <[bool; 4] as ::arrise::SerialSize>::SIZE]:),
+                parse_quote!([(); <bool as ::arrise::SerialSize>::SIZE]:),
+            ]),
+            predicates(quote!(Option<(T, u8)>, [bool; 4]), item)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(expand(
+            quote!(),
+            quote!(
+                struct S;
+            )
+        )
+        .is_err());
+        assert!(expand(
+            quote!(1 +),
+            quote!(
+                fn f() {}
+            )
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,75 @@
+//! Compares the bulk path for arrays of primitives against the generic per-element path.
+//!
+//! Run with `--features primitive_be` on a little-endian host (or `primitive_le` on
+//! a big-endian one) to measure the byte-swapping bulk path instead of the plain copy.
+
+#![allow(incomplete_features)]
+#![feature(generic_const_exprs)]
+#![feature(test)]
+
+extern crate test;
+
+use arrise::{Deserialize, SerialSize, Serialize};
+use core::ptr::NonNull;
+use test::{black_box, Bencher};
+
+const LEN: usize = 4096;
+
+/// An `f32` without the bulk path, (de-)serialized one element at a time
+/// in the byte order selected by the crate features.
+#[derive(Copy, Clone, Default)]
+struct Generic(f32);
+
+impl SerialSize for Generic {
+    const SIZE: usize = <f32 as SerialSize>::SIZE;
+}
+
+impl Serialize for Generic {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        self.0.serialize(buffer)
+    }
+}
+
+impl Deserialize for Generic {
+    type Error = core::convert::Infallible;
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(Generic(f32::deserialize(buffer)?));
+        Ok(())
+    }
+}
+
+fn frame() -> [f32; LEN] {
+    core::array::from_fn(|i| i as f32 / LEN as f32)
+}
+
+#[bench]
+fn serialize_bulk(b: &mut Bencher) {
+    let frame = frame();
+    let mut buffer = [0; <[f32; LEN] as SerialSize>::SIZE];
+    b.iter(|| black_box(&frame).serialize(black_box(&mut buffer)));
+}
+
+#[bench]
+fn serialize_generic(b: &mut Bencher) {
+    let frame = frame().map(Generic);
+    let mut buffer = [0; <[Generic; LEN] as SerialSize>::SIZE];
+    b.iter(|| black_box(&frame).serialize(black_box(&mut buffer)));
+}
+
+#[bench]
+fn deserialize_bulk(b: &mut Bencher) {
+    let mut buffer = [0; <[f32; LEN] as SerialSize>::SIZE];
+    frame().serialize(&mut buffer);
+    b.iter(|| <[f32; LEN]>::deserialize(black_box(&buffer)));
+}
+
+#[bench]
+fn deserialize_generic(b: &mut Bencher) {
+    let mut buffer = [0; <[Generic; LEN] as SerialSize>::SIZE];
+    frame().map(Generic).serialize(&mut buffer);
+    b.iter(|| <[Generic; LEN]>::deserialize(black_box(&buffer)));
+}
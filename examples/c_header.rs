@@ -0,0 +1,19 @@
+//! Print a C header for a message type.
+//!
+//! ```sh
+//! cargo run --example c_header --features alloc > telemetry.h
+//! ```
+//!
+//! Replace `Telemetry` with your own types implementing [`Schema`].
+//! For a layout description written by `schema_json`, use the `arrise c-header` command.
+
+use arrise::schema::{c, Schema};
+use core::net::SocketAddr;
+use core::time::Duration;
+
+/// A reading sent by a sensor: its id, an optional temperature, the uptime and the collector.
+type Telemetry = (u32, Option<f32>, Duration, SocketAddr);
+
+fn main() {
+    print!("{}", c::header("Telemetry", &Telemetry::SCHEMA));
+}
@@ -0,0 +1,21 @@
+//! Print a Wireshark dissector for a message type sent over UDP port 4000.
+//!
+//! ```sh
+//! cargo run --example lua_dissector --features alloc > ~/.local/lib/wireshark/plugins/telemetry.lua
+//! ```
+//!
+//! Replace `Telemetry` with your own types implementing [`Schema`].
+
+use arrise::schema::{lua, Schema};
+use core::net::SocketAddr;
+use core::time::Duration;
+
+/// A reading sent by a sensor: its id, an optional temperature, the uptime and the collector.
+type Telemetry = (u32, Option<f32>, Duration, SocketAddr);
+
+fn main() {
+    print!(
+        "{}",
+        lua::dissector("Telemetry", &Telemetry::SCHEMA, Some(4000))
+    );
+}
@@ -0,0 +1,18 @@
+//! Print a Python module for a message type.
+//!
+//! ```sh
+//! cargo run --example python_module --features alloc > telemetry.py
+//! ```
+//!
+//! Replace `Telemetry` with your own types implementing [`Schema`].
+
+use arrise::schema::{python, Schema};
+use core::net::SocketAddr;
+use core::time::Duration;
+
+/// A reading sent by a sensor: its id, an optional temperature, the uptime and the collector.
+type Telemetry = (u32, Option<f32>, Duration, SocketAddr);
+
+fn main() {
+    print!("{}", python::module("Telemetry", &Telemetry::SCHEMA));
+}
@@ -0,0 +1,19 @@
+//! Print the layout of a message type as JSON, to be read by the `arrise` binary.
+//!
+//! ```sh
+//! cargo run --example schema_json --features alloc > telemetry.json
+//! cargo run --bin arrise --features std -- dump telemetry.json message.bin
+//! ```
+//!
+//! Replace `Telemetry` with your own types implementing [`Schema`].
+
+use arrise::schema::{json, Schema};
+use core::net::SocketAddr;
+use core::time::Duration;
+
+/// A reading sent by a sensor: its id, an optional temperature, the uptime and the collector.
+type Telemetry = (u32, Option<f32>, Duration, SocketAddr);
+
+fn main() {
+    print!("{}", json::layout_to_json(&Telemetry::SCHEMA));
+}
@@ -0,0 +1,674 @@
+//! Inspect serialized buffers, given a layout description written by
+//! [`layout_to_json`](arrise::schema::json::layout_to_json),
+//! such as the one printed by the `schema_json` example.
+//!
+//! ```sh
+//! arrise dump telemetry.json message.bin
+//! arrise to-json telemetry.json message.hex --hex > message.json
+//! arrise from-json telemetry.json message.json > message.bin
+//! arrise compat telemetry-1.0.json telemetry.json
+//! arrise c-header telemetry.json Telemetry > telemetry.h
+//! ```
+
+use arrise::schema::c;
+use arrise::schema::compat::{compare, Change, ChangeKind};
+use arrise::schema::dynamic::{
+    decode_dynamic, encode_dynamic, DecodeDynamicError, EncodeDynamicError, Value,
+};
+use arrise::schema::json::{layout_from_json, value_from_json, value_to_json, JsonError};
+use arrise::schema::{Field, Kind, Layout, Variant};
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: arrise <command> <layout.json> [input] [--hex]
+       arrise compat <old.json> <new.json>
+       arrise c-header <layout.json> <name>
+
+Commands:
+  dump       print every value of a serialized buffer with its offset and bytes
+  to-json    convert a serialized buffer to JSON
+  from-json  convert JSON to a serialized buffer
+  compat     list the changes between two layouts, failing if any of them is breaking
+  c-header   print a C header declaring the layout as the type <name>
+
+The input is read from stdin if it is omitted or `-`.
+With `--hex`, buffers are read or written as hexadecimal text instead of raw bytes.
+";
+
+/// The width of the bytes column of dumps, which holds up to 8 bytes.
+const BYTES_WIDTH: usize = 8 * 3;
+
+#[derive(Debug)]
+enum Failure {
+    /// The command line is malformed.
+    Usage(String),
+    /// The input or layout cannot be read or is invalid.
+    Invalid(String),
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        | Ok(()) => ExitCode::SUCCESS,
+        | Err(Failure::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+        | Err(Failure::Invalid(message)) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Failure> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        print!("{USAGE}");
+        return Ok(());
+    }
+    let hex = args.iter().any(|arg| arg == "--hex");
+    let args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--hex")
+        .collect();
+    let (command, layout, input) = match args[..] {
+        | [command, layout] => (command, layout, "-"),
+        | [command, layout, input] => (command, layout, input),
+        | _ => {
+            return Err(Failure::Usage(String::from(
+                "expected a command and a layout",
+            )))
+        }
+    };
+    if !["dump", "to-json", "from-json", "compat", "c-header"].contains(&command) {
+        return Err(Failure::Usage(format!("unknown command `{command}`")));
+    }
+    if command == "compat" {
+        if input == "-" {
+            return Err(Failure::Usage(String::from("expected two layouts")));
+        }
+        return compat(read_layout(layout)?, read_layout(input)?);
+    }
+    if command == "c-header" {
+        if !is_c_identifier(input) {
+            return Err(Failure::Usage(String::from(
+                "expected a C identifier as the name",
+            )));
+        }
+        print!("{}", c::header(input, read_layout(layout)?));
+        return Ok(());
+    }
+
+    let layout = read_layout(layout)?;
+    let input_name = input;
+    let input = read(input)?;
+
+    let output = match command {
+        | "dump" => dump(layout, &buffer(input, hex)?)?.into_bytes(),
+        | "to-json" => value_to_json(&decode(layout, &buffer(input, hex)?)?).into_bytes(),
+        | _ => {
+            let json = String::from_utf8(input)
+                .map_err(|_| Failure::Invalid(format!("{input_name} is not UTF-8")))?;
+            let value = value_from_json(layout, &json)
+                .map_err(|error| json_failure(input_name, error))?;
+            let bytes = encode(layout, &value)?;
+            match hex {
+                | true => to_hex(&bytes).into_bytes(),
+                | false => bytes,
+            }
+        }
+    };
+    std::io::stdout()
+        .write_all(&output)
+        .map_err(|error| Failure::Invalid(format!("cannot write output: {error}")))
+}
+
+fn is_c_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Read the file at `path`, or stdin for `-`.
+fn read(path: &str) -> Result<Vec<u8>, Failure> {
+    let mut bytes = Vec::new();
+    let result = match path {
+        | "-" => std::io::stdin().read_to_end(&mut bytes).map(|_| ()),
+        | path => std::fs::read(path).map(|contents| bytes = contents),
+    };
+    result.map_err(|error| Failure::Invalid(format!("cannot read {path}: {error}")))?;
+    Ok(bytes)
+}
+
+fn read_layout(path: &str) -> Result<&'static Layout, Failure> {
+    let json = String::from_utf8(read(path)?)
+        .map_err(|_| Failure::Invalid(format!("{path} is not UTF-8")))?;
+    layout_from_json(&json).map_err(|error| json_failure(path, error))
+}
+
+fn buffer(input: Vec<u8>, hex: bool) -> Result<Vec<u8>, Failure> {
+    match hex {
+        | true => from_hex(&input),
+        | false => Ok(input),
+    }
+}
+
+/// Parse pairs of hex digits, ignoring whitespace.
+fn from_hex(text: &[u8]) -> Result<Vec<u8>, Failure> {
+    let mut digits = Vec::new();
+    for (position, byte) in text.iter().enumerate() {
+        match (*byte as char).to_digit(16) {
+            | Some(digit) => digits.push(digit as u8),
+            | None if byte.is_ascii_whitespace() => {}
+            | None => {
+                return Err(Failure::Invalid(format!(
+                    "invalid hex digit at character {position}"
+                )))
+            }
+        }
+    }
+    if digits.len() % 2 != 0 {
+        return Err(Failure::Invalid(String::from("odd number of hex digits")));
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/// Format `bytes` as hex, 16 bytes per line.
+fn to_hex(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    for line in bytes.chunks(16) {
+        output.push_str(&hex_bytes(line));
+        output.push('\n');
+    }
+    output
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    let hex: Vec<String> = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    hex.join(" ")
+}
+
+fn json_failure(name: &str, error: JsonError) -> Failure {
+    Failure::Invalid(match error {
+        | JsonError::Syntax { position } => {
+            format!("{name}: invalid JSON at byte {position}")
+        }
+        | JsonError::Unexpected { path } => format!("{name}: unexpected JSON at {path}"),
+    })
+}
+
+fn decode(layout: &Layout, buffer: &[u8]) -> Result<Value, Failure> {
+    let (error, offset) = match decode_dynamic(layout, buffer) {
+        | Ok(value) => return Ok(value),
+        | Err(DecodeDynamicError::Length) => {
+            return Err(Failure::Invalid(format!(
+                "expected {} bytes, got {}",
+                layout.size,
+                buffer.len()
+            )))
+        }
+        | Err(DecodeDynamicError::IllegalBitPattern { offset }) => {
+            ("illegal bit pattern", offset)
+        }
+        | Err(DecodeDynamicError::CharTryFromError { offset }) => {
+            ("invalid char", offset)
+        }
+        | Err(DecodeDynamicError::IllegalTag { offset }) => ("illegal tag", offset),
+    };
+    Err(Failure::Invalid(format!(
+        "{error} at byte {offset} (0x{offset:x}) in {}: {:02x}",
+        path_at(layout, buffer, offset),
+        buffer[offset]
+    )))
+}
+
+fn encode(layout: &Layout, value: &Value) -> Result<Vec<u8>, Failure> {
+    let mut buffer = vec![0; layout.size];
+    match encode_dynamic(layout, value, &mut buffer) {
+        | Ok(()) => Ok(buffer),
+        | Err(EncodeDynamicError::Length) => {
+            unreachable!("the buffer has the size of the layout")
+        }
+        | Err(EncodeDynamicError::Mismatch { offset }) => Err(Failure::Invalid(format!(
+            "value does not match the layout at byte {offset} (0x{offset:x})"
+        ))),
+        | Err(EncodeDynamicError::OutOfRange { offset }) => Err(Failure::Invalid(
+            format!("value out of range at byte {offset} (0x{offset:x})"),
+        )),
+    }
+}
+
+/// The path to the innermost value containing the byte at `offset`,
+/// written like the paths of [`JsonError::Unexpected`].
+fn path_at(layout: &Layout, buffer: &[u8], offset: usize) -> String {
+    let mut path = String::from("$");
+    let mut layout = layout;
+    let mut base = 0;
+    loop {
+        let fields = match layout.kind {
+            | Kind::Array { element, .. } if element.size != 0 => {
+                let i = (offset - base) / element.size;
+                write!(path, "[{i}]").unwrap();
+                base += i * element.size;
+                layout = element;
+                continue;
+            }
+            | Kind::Struct { fields } => fields,
+            | Kind::Enum { tag, variants, .. } if offset - base >= tag.size => {
+                let Some(variant) = variant_at(tag, variants, &buffer[base..]) else {
+                    break;
+                };
+                write!(path, ".{}", variant.name).unwrap();
+                match variant.fields {
+                    // a single positional field is written as the variant itself
+                    | [field] if field.name == "0" && contains(field, base, offset) => {
+                        base += field.offset;
+                        layout = field.layout;
+                        continue;
+                    }
+                    | fields => fields,
+                }
+            }
+            | _ => break,
+        };
+        let Some(field) = fields.iter().find(|field| contains(field, base, offset))
+        else {
+            break;
+        };
+        match field.name.parse::<usize>() {
+            | Ok(i) => write!(path, "[{i}]").unwrap(),
+            | Err(_) => write!(path, ".{}", field.name).unwrap(),
+        }
+        base += field.offset;
+        layout = field.layout;
+    }
+    path
+}
+
+/// The variant selected by the `tag` at the start of `buffer`, if any.
+fn variant_at(
+    tag: &Layout,
+    variants: &'static [Variant],
+    buffer: &[u8],
+) -> Option<&'static Variant> {
+    let tag = match decode_dynamic(tag, &buffer[..tag.size]) {
+        | Ok(Value::Bool(tag)) => tag as i64,
+        | Ok(Value::Unsigned(tag)) => tag as i64,
+        | Ok(Value::Signed(tag)) => tag as i64,
+        | _ => return None,
+    };
+    variants.iter().find(|variant| variant.tag == tag)
+}
+
+/// Whether `field` of a layout at `base` contains the byte at `offset`.
+fn contains(field: &Field, base: usize, offset: usize) -> bool {
+    (base + field.offset..base + field.offset + field.layout.size).contains(&offset)
+}
+
+/// Print the changes from `old` to `new`, failing if any of them is breaking.
+fn compat(old: &Layout, new: &Layout) -> Result<(), Failure> {
+    let changes = compare(old, new);
+    if changes.is_empty() {
+        println!("no changes");
+    }
+    for change in &changes {
+        let class = match change.breaking {
+            | true => "breaking",
+            | false => "compatible",
+        };
+        println!("{class:<10}  {}: {}", change.path, describe(change));
+    }
+    match changes.iter().filter(|change| change.breaking).count() {
+        | 0 => Ok(()),
+        | 1 => Err(Failure::Invalid(String::from("1 breaking change"))),
+        | count => Err(Failure::Invalid(format!("{count} breaking changes"))),
+    }
+}
+
+fn describe(change: &Change) -> String {
+    match change.kind {
+        | ChangeKind::Size { old, new } => format!("SIZE changed from {old} to {new}"),
+        | ChangeKind::Added { offset, size } => {
+            format!("added {size} bytes at offset {offset}")
+        }
+        | ChangeKind::Removed { offset, size } => {
+            format!("removed {size} bytes at offset {offset}")
+        }
+        | ChangeKind::Renamed { old } => format!("renamed from `{old}`"),
+        | ChangeKind::Resized { old, new } => {
+            format!("resized from {old} to {new} bytes")
+        }
+        | ChangeKind::Moved { old, new } => format!("moved from offset {old} to {new}"),
+        | ChangeKind::Retyped { old, new } => {
+            format!("changed from {} to {}", type_name(&old), type_name(&new))
+        }
+        | ChangeKind::VariantAdded { tag } => format!("variant added with tag {tag}"),
+        | ChangeKind::VariantRemoved { tag } => format!("variant with tag {tag} removed"),
+        | ChangeKind::TagChanged { old, new } => {
+            format!("tag changed from {old} to {new}")
+        }
+        | ChangeKind::ZeroedPadding { new: true, .. } => {
+            String::from("padding now has to be zero")
+        }
+        | ChangeKind::ZeroedPadding { new: false, .. } => {
+            String::from("padding no longer has to be zero")
+        }
+    }
+}
+
+fn type_name(layout: &Layout) -> String {
+    match layout.kind {
+        | Kind::Primitive { primitive, endian } => {
+            format!("{} ({primitive:?}, {endian:?} endian)", layout.name)
+        }
+        | Kind::Array { element, len } => format!("[{}; {len}]", element.name),
+        | Kind::Struct { .. } => format!("struct {}", layout.name),
+        | Kind::Enum { .. } => format!("enum {}", layout.name),
+    }
+}
+
+/// List every value in `buffer` with its offset and bytes, indented by nesting.
+fn dump(layout: &Layout, buffer: &[u8]) -> Result<String, Failure> {
+    let value = decode(layout, buffer)?;
+    let mut output = format!("{:<8}  {:<BYTES_WIDTH$}  value\n", "offset", "bytes");
+    dump_value(&mut output, layout, &value, buffer, 0, 0, "$");
+    Ok(output)
+}
+
+fn dump_value(
+    output: &mut String,
+    layout: &Layout,
+    value: &Value,
+    buffer: &[u8],
+    offset: usize,
+    depth: usize,
+    label: &str,
+) {
+    match (layout.kind, value) {
+        | (Kind::Array { element, len }, Value::Array(values)) => {
+            let description = format!("{label}: [{}; {len}]", element.name);
+            dump_line(output, offset, &[], depth, &description);
+            for (i, value) in values.iter().enumerate() {
+                let offset = offset + i * element.size;
+                dump_value(
+                    output,
+                    element,
+                    value,
+                    buffer,
+                    offset,
+                    depth + 1,
+                    &format!("[{i}]"),
+                );
+            }
+        }
+        | (Kind::Struct { fields }, value) => {
+            dump_line(
+                output,
+                offset,
+                &[],
+                depth,
+                &format!("{label}: {}", layout.name),
+            );
+            dump_fields(
+                output,
+                fields,
+                &field_values(value),
+                buffer,
+                offset,
+                depth + 1,
+            );
+        }
+        | (Kind::Enum { tag, variants, .. }, value) => {
+            let values = match value {
+                | Value::Option(None) => Vec::new(),
+                | Value::Option(Some(value)) => vec![&**value],
+                | Value::Enum { fields, .. } => {
+                    fields.iter().map(|(_, value)| value).collect()
+                }
+                | _ => unreachable!("decoded from the layout"),
+            };
+            let variant = variant_at(tag, variants, &buffer[offset..])
+                .expect("decoded from the layout");
+            let description = format!("{label}: {} = {}", layout.name, variant.name);
+            let tag_bytes = &buffer[offset..offset + tag.size];
+            dump_line(output, offset, tag_bytes, depth, &description);
+            dump_fields(output, variant.fields, &values, buffer, offset, depth + 1);
+        }
+        | (Kind::Primitive { .. }, value) => {
+            let description = format!("{label}: {} = {}", layout.name, primitive(value));
+            let bytes = &buffer[offset..offset + layout.size];
+            dump_line(output, offset, bytes, depth, &description);
+        }
+        | _ => unreachable!("decoded from the layout"),
+    }
+}
+
+/// Dump `fields` of a layout at `base`.
+fn dump_fields(
+    output: &mut String,
+    fields: &[Field],
+    values: &[&Value],
+    buffer: &[u8],
+    base: usize,
+    depth: usize,
+) {
+    for (field, value) in fields.iter().zip(values) {
+        let offset = base + field.offset;
+        dump_value(
+            output,
+            field.layout,
+            value,
+            buffer,
+            offset,
+            depth,
+            field.name,
+        );
+    }
+}
+
+fn field_values(value: &Value) -> Vec<&Value> {
+    match value {
+        | Value::Tuple(values) => values.iter().collect(),
+        | Value::Struct(fields) => fields.iter().map(|(_, value)| value).collect(),
+        | _ => unreachable!("decoded from the layout"),
+    }
+}
+
+fn primitive(value: &Value) -> String {
+    match value {
+        | Value::Unsigned(value) => value.to_string(),
+        | Value::Signed(value) => value.to_string(),
+        | Value::F32(value) => format!("{value:?}"),
+        | Value::F64(value) => format!("{value:?}"),
+        | Value::Bool(value) => value.to_string(),
+        | Value::Char(value) => format!("{value:?}"),
+        | _ => unreachable!("decoded from a primitive"),
+    }
+}
+
+/// Write a line of a dump, eliding all but the first 7 of more than 8 bytes.
+fn dump_line(
+    output: &mut String,
+    offset: usize,
+    bytes: &[u8],
+    depth: usize,
+    description: &str,
+) {
+    let bytes = match bytes.len() > 8 {
+        | true => format!("{} …", hex_bytes(&bytes[..7])),
+        | false => hex_bytes(bytes),
+    };
+    writeln!(
+        output,
+        "{offset:08x}  {bytes:<BYTES_WIDTH$}  {:indent$}{description}",
+        "",
+        indent = depth * 2
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode, describe, dump, encode, from_hex, is_c_identifier, path_at, run, to_hex,
+        Failure,
+    };
+    use arrise::schema::compat::compare;
+    use arrise::schema::json::{
+        layout_from_json, layout_to_json, value_from_json, value_to_json,
+    };
+    use arrise::schema::Schema;
+    use arrise::Serialize;
+    use std::net::SocketAddr;
+
+    type Message = (u16, Option<char>, SocketAddr);
+
+    fn message() -> Vec<u8> {
+        let message: Message = (7, Some('a'), "10.0.0.1:80".parse().unwrap());
+        message.to_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_dump() {
+        // the layout is read back from JSON, like the binary does
+        let layout = layout_from_json(&layout_to_json(&Message::SCHEMA)).unwrap();
+        let dump = dump(layout, &message()).unwrap();
+        let lines: Vec<&str> = dump.lines().collect();
+        let (u16_bytes, char_bytes) = match cfg!(feature = "primitive_be") {
+            | true => ("00 07", "00 00 00 61"),
+            | false => ("07 00", "61 00 00 00"),
+        };
+        assert_eq!(format!("00000000  {u16_bytes:<24}    0: u16 = 7"), lines[2]);
+        assert_eq!(
+            "00000002  01                          1: Option = Some",
+            lines[3]
+        );
+        assert_eq!(
+            format!("00000003  {char_bytes:<24}      0: char = 'a'"),
+            lines[4]
+        );
+        assert_eq!(
+            "00000007  00                          2: SocketAddr = V4",
+            lines[5]
+        );
+        assert_eq!(
+            "00000008                                0: SocketAddrV4",
+            lines[6]
+        );
+        assert_eq!(
+            "00000008                                  ip: [u8; 4]",
+            lines[7]
+        );
+        assert_eq!(
+            "0000000c  00 50                           port: u16 = 80",
+            lines[12]
+        );
+        assert_eq!(13, lines.len());
+    }
+
+    #[test]
+    fn test_bool_tag() {
+        // laid out like an `Option`, but with a differently named `None` variant
+        let json =
+            layout_to_json(&<Option<u8>>::SCHEMA).replace("\"None\"", "\"Nothing\"");
+        let layout = layout_from_json(&json).unwrap();
+        for (bytes, name) in [([0, 0], "Nothing"), ([1, 5], "Some")] {
+            let dump = dump(layout, &bytes).unwrap();
+            assert!(dump
+                .lines()
+                .nth(1)
+                .unwrap()
+                .ends_with(&format!("$: Option = {name}")));
+            let value = decode(layout, &bytes).map_err(|_| ()).unwrap();
+            let json = value_to_json(&value);
+            assert!(json.contains(name));
+            assert_eq!(Ok(value), value_from_json(layout, &json).map_err(|_| ()));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let layout = &Message::SCHEMA;
+        let mut buffer = message();
+        buffer[3..7].copy_from_slice(&0xd800u32.to_bytes());
+        let Err(Failure::Invalid(error)) = decode(layout, &buffer) else {
+            panic!("surrogates are invalid chars");
+        };
+        assert!(error.starts_with("invalid char at byte 3 (0x3) in $[1].Some: "));
+
+        buffer = message();
+        buffer[20] = 1;
+        assert_eq!("$[2].V4", path_at(layout, &buffer, 20));
+        assert_eq!("$[2].V4.ip[2]", path_at(layout, &buffer, 10));
+        assert_eq!("$[2]", path_at(layout, &buffer, 7));
+
+        let Err(Failure::Invalid(error)) = decode(layout, &buffer[1..]) else {
+            panic!("the buffer is too short");
+        };
+        assert_eq!("expected 34 bytes, got 33", error);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let layout = &Message::SCHEMA;
+        let json = "[65535, \"None\", {\"V4\": {\"ip\": [1, 2, 3, 4], \"port\": 5}}]";
+        let value = value_from_json(layout, json).unwrap();
+        let bytes = encode(layout, &value).unwrap();
+        assert_eq!(Ok(value), decode(layout, &bytes).map_err(|_| ()));
+        assert_eq!(bytes, from_hex(to_hex(&bytes).as_bytes()).unwrap());
+        assert_eq!(
+            "00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n10\n",
+            to_hex(&core::array::from_fn::<u8, 17, _>(|i| i as u8))
+        );
+
+        let value = value_from_json(layout, &json.replace("65535", "65536")).unwrap();
+        assert!(matches!(encode(layout, &value), Err(Failure::Invalid(_))));
+        assert!(matches!(from_hex(b"0g"), Err(Failure::Invalid(_))));
+    }
+
+    #[test]
+    fn test_compat() {
+        let old = <(u8, Option<u16>)>::SCHEMA;
+        let new = <(u8, Option<i32>)>::SCHEMA;
+        let changes: Vec<String> = compare(&old, &new)
+            .iter()
+            .map(|change| format!("{}: {}", change.path, describe(change)))
+            .collect();
+        assert_eq!(
+            [
+                "$: SIZE changed from 4 to 6",
+                "$[1]: resized from 3 to 5 bytes",
+                "$[1].Some: resized from 2 to 4 bytes",
+            ],
+            &changes[..3]
+        );
+        assert!(changes[3].starts_with("$[1].Some: changed from u16 (Unsigned, "));
+        assert!(changes[3].contains(" endian) to i32 (Signed, "));
+    }
+
+    #[test]
+    fn test_c_header() {
+        assert!(is_c_identifier("Telemetry_2"));
+        assert!(is_c_identifier("_t"));
+        assert!(!is_c_identifier("2d"));
+        assert!(!is_c_identifier("-"));
+        assert!(!is_c_identifier("a-b"));
+
+        let dir = std::env::temp_dir().join(format!("arrise-bin-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("message.json");
+        std::fs::write(&path, layout_to_json(&Message::SCHEMA)).unwrap();
+        let args =
+            |name: &str| ["c-header", path.to_str().unwrap(), name].map(String::from);
+        let valid = run(&args("Message"));
+        let invalid = run(&args("-"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(valid.is_ok());
+        assert!(matches!(invalid, Err(Failure::Usage(_))));
+    }
+}
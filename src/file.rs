@@ -0,0 +1,311 @@
+//! Append-only files of fixed-size records.
+//!
+//! Record `n` of a [`RecordFile<T>`] always lives at byte `n * T::SIZE`.
+
+use crate::{Deserialize, SerialSize, Serialize};
+use core::marker::PhantomData;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// When a [`RecordFile`] flushes its writes to the storage device.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum SyncMode {
+    /// Only on explicit calls to [`RecordFile::sync`].
+    #[default]
+    Manual,
+    /// After every write, syncing the data but not necessarily the metadata.
+    /// See [`File::sync_data`].
+    Data,
+    /// After every write, syncing both data and metadata.
+    /// See [`File::sync_all`].
+    All,
+}
+
+#[derive(Debug)]
+pub enum ReadRecordError<T> {
+    Io(io::Error),
+    Data(T),
+}
+
+impl<T> From<io::Error> for ReadRecordError<T> {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// A file of consecutive serialized `T`s.
+#[derive(Debug)]
+pub struct RecordFile<T: SerialSize> {
+    file: File,
+    len: u64,
+    sync_mode: SyncMode,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: SerialSize> RecordFile<T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    const STRIDE: u64 = {
+        assert!(
+            <T as SerialSize>::SIZE != 0,
+            "records must not be zero-sized"
+        );
+        <T as SerialSize>::SIZE as u64
+    };
+
+    /// Open the file at `path` for reading and writing, creating it if it does not exist.
+    /// See [`from_file`](Self::from_file).
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        Self::from_file(file)
+    }
+
+    /// Use `file`, which must be open for reading and writing, as a record file.
+    ///
+    /// A torn record at the end of `file`, left behind by an interrupted append,
+    /// is truncated.
+    pub fn from_file(file: File) -> io::Result<Self> {
+        let bytes = file.metadata()?.len();
+        let len = bytes / Self::STRIDE;
+        if bytes % Self::STRIDE != 0 {
+            file.set_len(len * Self::STRIDE)?;
+        }
+
+        Ok(Self {
+            file,
+            len,
+            sync_mode: SyncMode::default(),
+            _type: PhantomData,
+        })
+    }
+
+    pub fn sync_mode(&self) -> SyncMode {
+        self.sync_mode
+    }
+
+    pub fn set_sync_mode(&mut self, sync_mode: SyncMode) {
+        self.sync_mode = sync_mode
+    }
+
+    /// The number of records.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Read the serialized bytes of the record at `index`.
+    pub fn read_bytes(
+        &mut self,
+        index: u64,
+    ) -> io::Result<[u8; <T as SerialSize>::SIZE]> {
+        self.seek(index)?;
+        let mut buffer = [0; <T as SerialSize>::SIZE];
+        self.file.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Overwrite the record at `index` with the serialized bytes in `buffer`.
+    pub fn write_bytes(
+        &mut self,
+        index: u64,
+        buffer: &[u8; <T as SerialSize>::SIZE],
+    ) -> io::Result<()> {
+        self.seek(index)?;
+        self.file.write_all(buffer)?;
+        self.sync_after_write()
+    }
+
+    /// Append the serialized bytes in `buffer` as a new record.
+    /// Returns the index of the new record.
+    pub fn append_bytes(
+        &mut self,
+        buffer: &[u8; <T as SerialSize>::SIZE],
+    ) -> io::Result<u64> {
+        let index = self.len;
+        self.file.seek(SeekFrom::Start(index * Self::STRIDE))?;
+        self.file.write_all(buffer)?;
+        self.len += 1;
+        self.sync_after_write()?;
+        Ok(index)
+    }
+
+    /// Shorten the file to `len` records.
+    /// Has no effect if the file holds `len` or fewer records.
+    pub fn truncate(&mut self, len: u64) -> io::Result<()> {
+        if len < self.len {
+            self.file.set_len(len * Self::STRIDE)?;
+            self.len = len;
+            self.sync_after_write()?;
+        }
+        Ok(())
+    }
+
+    /// Flush all writes to the storage device, see [`File::sync_all`].
+    pub fn sync(&self) -> io::Result<()> {
+        self.file.sync_all()
+    }
+
+    pub fn into_file(self) -> File {
+        self.file
+    }
+
+    fn seek(&mut self, index: u64) -> io::Result<()> {
+        if index >= self.len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "record index {index} out of bounds for {} records",
+                    self.len
+                ),
+            ));
+        }
+        self.file.seek(SeekFrom::Start(index * Self::STRIDE))?;
+        Ok(())
+    }
+
+    fn sync_after_write(&self) -> io::Result<()> {
+        match self.sync_mode {
+            | SyncMode::Manual => Ok(()),
+            | SyncMode::Data => self.file.sync_data(),
+            | SyncMode::All => self.file.sync_all(),
+        }
+    }
+}
+
+impl<T: Serialize> RecordFile<T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Append `value` as a new record.
+    /// Returns the index of the new record.
+    pub fn append(&mut self, value: &T) -> io::Result<u64> {
+        self.append_bytes(&value.to_bytes())
+    }
+
+    /// Overwrite the record at `index` with `value`.
+    pub fn write(&mut self, index: u64, value: &T) -> io::Result<()> {
+        self.write_bytes(index, &value.to_bytes())
+    }
+}
+
+impl<T: Deserialize> RecordFile<T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Read and deserialize the record at `index`.
+    pub fn read(&mut self, index: u64) -> Result<T, ReadRecordError<T::Error>> {
+        T::deserialize(&self.read_bytes(index)?).map_err(ReadRecordError::Data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReadRecordError, RecordFile, SyncMode};
+    use crate::impls::core::option::DeserializeOptionError;
+    use std::fs;
+    use std::io::{ErrorKind, Write};
+    use std::path::PathBuf;
+
+    /// A file in the temporary directory, which is removed on drop.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("arrise-{}-{name}.records", std::process::id()));
+            let _ = fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let temp = TempFile::new("append");
+        let mut file = RecordFile::<(u32, Option<char>)>::open(&temp.0).unwrap();
+        assert!(file.is_empty());
+
+        assert_eq!(0, file.append(&(1, Some('a'))).unwrap());
+        assert_eq!(1, file.append(&(2, None)).unwrap());
+        file.write(0, &(3, Some('c'))).unwrap();
+        drop(file);
+
+        let mut file = RecordFile::<(u32, Option<char>)>::open(&temp.0).unwrap();
+        assert_eq!(2, file.len());
+        assert_eq!((3, Some('c')), file.read(0).unwrap());
+        assert_eq!((2, None), file.read(1).unwrap());
+        assert_eq!(9 * 2, fs::metadata(&temp.0).unwrap().len());
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let temp = TempFile::new("bounds");
+        let mut file = RecordFile::<u16>::open(&temp.0).unwrap();
+        file.append(&7).unwrap();
+
+        let Err(ReadRecordError::Io(error)) = file.read(1) else {
+            panic!("read out of bounds");
+        };
+        assert_eq!(ErrorKind::InvalidInput, error.kind());
+        assert_eq!(
+            ErrorKind::InvalidInput,
+            file.write(1, &8).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn test_torn_record() {
+        let temp = TempFile::new("torn");
+        let mut file = RecordFile::<u64>::open(&temp.0).unwrap();
+        file.set_sync_mode(SyncMode::Data);
+        file.append(&1).unwrap();
+        file.append(&2).unwrap();
+
+        // an interrupted append
+        let mut raw = file.into_file();
+        raw.write_all(&[0xff; 3]).unwrap();
+        drop(raw);
+        assert_eq!(8 * 2 + 3, fs::metadata(&temp.0).unwrap().len());
+
+        let mut file = RecordFile::<u64>::open(&temp.0).unwrap();
+        assert_eq!(2, file.len());
+        assert_eq!(8 * 2, fs::metadata(&temp.0).unwrap().len());
+        assert_eq!(2, file.append(&3).unwrap());
+        assert_eq!(3, file.read(2).unwrap());
+    }
+
+    #[test]
+    fn test_truncate_and_invalid() {
+        let temp = TempFile::new("truncate");
+        let mut file = RecordFile::<Option<u8>>::open(&temp.0).unwrap();
+        file.append(&Some(1)).unwrap();
+        file.append(&None).unwrap();
+        file.write_bytes(1, &[7, 0]).unwrap();
+
+        assert!(matches!(
+            file.read(1),
+            Err(ReadRecordError::Data(DeserializeOptionError::IllegalTag))
+        ));
+
+        file.truncate(1).unwrap();
+        file.sync().unwrap();
+        assert_eq!(1, file.len());
+        assert_eq!(2, fs::metadata(&temp.0).unwrap().len());
+        assert_eq!(Some(1), file.read(0).unwrap());
+    }
+}
@@ -1,5 +1,150 @@
+/// Implement [`SerialSize`](crate::SerialSize), [`Serialize`](crate::Serialize),
+/// [`Deserialize`](crate::Deserialize) and [`Schema`](crate::schema::Schema)
+/// for a `repr(transparent)` wrapper `$wrapper<T>` by forwarding to `T`.
+/// `$inner` borrows the wrapped `T` from `$value: &$wrapper<T>`.
+///
+/// Deserializing in place forwards to `T` as well, which drops the value being
+/// replaced. Pass `leak` to keep the default, which overwrites the whole wrapper
+/// instead, e.g. for `ManuallyDrop`.
+macro_rules! impl_for_transparent {
+    ($wrapper:ident, $value:ident => $inner:expr) => {
+        impl_for_transparent!(@impl $wrapper, $value => $inner, {
+            fn deserialize_in_place(
+                &mut self,
+                buffer: &[u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = split_array::split_arr(buffer);
+                debug_assert_eq!(0, tail.len());
+                // Safety:
+                // `$wrapper<T>` is `repr(transparent)`
+                let inner = unsafe { &mut *(self as *mut Self).cast::<T>() };
+                <T as crate::Deserialize>::deserialize_in_place(inner, head)
+            }
+
+            fn deserialize_in_place_unchecked(
+                &mut self,
+                buffer: &[u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = split_array::split_arr(buffer);
+                debug_assert_eq!(0, tail.len());
+                // Safety:
+                // `$wrapper<T>` is `repr(transparent)`
+                let inner = unsafe { &mut *(self as *mut Self).cast::<T>() };
+                <T as crate::Deserialize>::deserialize_in_place_unchecked(inner, head)
+            }
+        });
+    };
+    ($wrapper:ident, $value:ident => $inner:expr, leak) => {
+        impl_for_transparent!(@impl $wrapper, $value => $inner, {});
+    };
+    (@impl $wrapper:ident, $value:ident => $inner:expr, { $($in_place:tt)* }) => {
+        impl<T: crate::SerialSize> crate::SerialSize for $wrapper<T> {
+            const SIZE: usize = <T as crate::SerialSize>::SIZE;
+        }
+
+        impl<T: crate::schema::Schema> crate::schema::Schema for $wrapper<T> {
+            const SCHEMA: crate::schema::Layout = <T as crate::schema::Schema>::SCHEMA;
+        }
+
+        impl<T: crate::Serialize> crate::Serialize for $wrapper<T>
+        where
+            [(); <Self as crate::SerialSize>::SIZE - <T as crate::SerialSize>::SIZE]:,
+        {
+            fn serialize(&self, buffer: &mut [u8; <Self as crate::SerialSize>::SIZE]) {
+                let (head, tail) = split_array::split_arr_mut(buffer);
+                debug_assert_eq!(0, tail.len());
+                let $value = self;
+                <T as crate::Serialize>::serialize($inner, head)
+            }
+
+            fn try_serialize(
+                &self,
+                buffer: &mut [u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), crate::SerializeError> {
+                let (head, tail) = split_array::split_arr_mut(buffer);
+                debug_assert_eq!(0, tail.len());
+                let $value = self;
+                <T as crate::Serialize>::try_serialize($inner, head)
+            }
+        }
+
+        impl<T: crate::Deserialize> crate::Deserialize for $wrapper<T>
+        where
+            [(); <Self as crate::SerialSize>::SIZE - <T as crate::SerialSize>::SIZE]:,
+        {
+            type Error = <T as crate::Deserialize>::Error;
+
+            fn validate(
+                buffer: &[u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = split_array::split_arr(buffer);
+                debug_assert_eq!(0, tail.len());
+                <T as crate::Deserialize>::validate(head)
+            }
+
+            $($in_place)*
+
+            unsafe fn deserialize_raw(
+                into: core::ptr::NonNull<Self>,
+                buffer: &[u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = split_array::split_arr(buffer);
+                debug_assert_eq!(0, tail.len());
+                // Safety:
+                // `$wrapper<T>` is `repr(transparent)`
+                unsafe { <T as crate::Deserialize>::deserialize_raw(into.cast(), head) }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+pub mod alloc;
 pub mod core;
 pub mod primitive;
+#[cfg(feature = "std")]
+pub mod std;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 pub struct IllegalBitPattern;
+
+/// Split an uninitialized buffer like [`split_array::split_arr_mut`].
+pub(crate) fn split_uninit_mut<const LEN: usize, const LHS: usize>(
+    buffer: &mut ::core::mem::MaybeUninit<[u8; LEN]>,
+) -> (
+    &mut ::core::mem::MaybeUninit<[u8; LHS]>,
+    &mut ::core::mem::MaybeUninit<[u8; LEN - LHS]>,
+)
+where
+    [(); LEN - LHS]:,
+{
+    let ptr = buffer.as_mut_ptr().cast::<u8>();
+    unsafe {
+        // Safety:
+        // - `LHS <= LEN`, so both halves lie within `buffer` and do not overlap
+        // - `MaybeUninit<[u8; _]>` has an alignment of 1 and accepts any contents
+        (&mut *ptr.cast(), &mut *ptr.add(LHS).cast())
+    }
+}
+
+/// Serialize `value` into the uninitialized `buffer` with
+/// [`Serialize::serialize_uninit`](crate::Serialize::serialize_uninit).
+/// Afterwards, `buffer` is initialized, even if an override returned a reference
+/// to other memory: its contents are then copied into `buffer`.
+#[inline(always)]
+pub(crate) fn serialize_uninit_into<T: crate::Serialize + ?Sized>(
+    value: &T,
+    buffer: &mut ::core::mem::MaybeUninit<[u8; <T as crate::SerialSize>::SIZE]>,
+) where
+    [(); <T as crate::SerialSize>::SIZE]:,
+{
+    let ptr = buffer.as_mut_ptr();
+    let written = value.serialize_uninit(buffer);
+    if !::core::ptr::eq(written, ptr) {
+        // Safety:
+        // - `written` is valid for reads and `ptr` for writes of `SIZE` bytes
+        // - `written` is a live mutable reference other than `buffer`,
+        //   so the two do not overlap
+        unsafe { ::core::ptr::copy_nonoverlapping(written, ptr, 1) }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod boxed;
+pub mod rc;
+pub mod sync;
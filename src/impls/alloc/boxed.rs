@@ -0,0 +1,155 @@
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
+use alloc::boxed::Box;
+use core::ptr::NonNull;
+use split_array::SplitArray;
+
+impl<T: SerialSize> SerialSize for Box<T> {
+    const SIZE: usize = <T as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for Box<T> {
+    const SCHEMA: Layout = T::SCHEMA;
+}
+
+impl<T: Serialize> Serialize for Box<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        (**self).serialize(head)
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        (**self).try_serialize(head)
+    }
+}
+
+/// The value is deserialized directly into a fresh heap allocation,
+/// so large values never pass through the stack.
+impl<T: Deserialize> Deserialize for Box<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = <T as Deserialize>::Error;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        T::validate(head)
+    }
+
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        (**self).deserialize_in_place(head)
+    }
+
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        (**self).deserialize_in_place_unchecked(head)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        let mut value = Box::<T>::new_uninit();
+        T::deserialize_into_uninit(&mut value, head)?;
+        unsafe {
+            // Safety:
+            // - `deserialize_into_uninit` just initialized `value`
+            // - `into` is valid for writes
+            into.write(value.assume_init());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+    use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use alloc::sync::Arc;
+    use alloc::vec;
+
+    #[test]
+    fn test_box() {
+        assert_serial_eq!(Box<u32>, &Box::new(0x12345678));
+        assert_serial_eq!(Box<(bool, Box<char>)>, &Box::new((true, Box::new('b'))));
+        assert_serial_eq!(Option<Box<i8>>, &Some(Box::new(-8)));
+    }
+
+    #[test]
+    fn test_in_place() {
+        let mut value = Box::new((1u8, [2u32; 4]));
+        let ptr: *const (u8, [u32; 4]) = &*value;
+
+        let mut buf = [0; <Box<(u8, [u32; 4])> as SerialSize>::SIZE];
+        (9u8, [7u32; 4]).serialize(&mut buf);
+        value
+            .deserialize_in_place(&buf)
+            .expect("deserialization failed");
+
+        assert_eq!((9, [7; 4]), *value);
+        assert_eq!(ptr, &*value as *const _);
+    }
+
+    #[test]
+    fn test_large() {
+        // considerably larger than the default stack size of test threads
+        const LEN: usize = 16 << 20;
+        type Large = [u8; LEN];
+
+        let mut buffer = vec![0; <Box<Large> as SerialSize>::SIZE];
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let buffer: &[u8; <Box<Large> as SerialSize>::SIZE] =
+            buffer.as_slice().try_into().unwrap();
+
+        let value = Box::<Large>::deserialize(buffer).expect("deserialization failed");
+        assert!(value.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+
+        let mut reserialized = vec![0; <Box<Large> as SerialSize>::SIZE];
+        value.serialize(reserialized.as_mut_slice().try_into().unwrap());
+        assert_eq!(buffer.as_slice(), reserialized);
+
+        // a shared `Rc` or `Arc` is replaced by a fresh allocation
+        let zeroes = vec![0; <Large as SerialSize>::SIZE];
+        let zeroes: &[u8; <Large as SerialSize>::SIZE] =
+            zeroes.as_slice().try_into().unwrap();
+        let mut rc = Rc::<Large>::deserialize(zeroes).expect("deserialization failed");
+        let shared = Rc::clone(&rc);
+        rc.deserialize_in_place(buffer)
+            .expect("deserialization failed");
+        assert!(rc.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+        assert!(shared.iter().all(|&byte| byte == 0));
+
+        let mut arc = Arc::<Large>::deserialize(zeroes).expect("deserialization failed");
+        let shared = Arc::clone(&arc);
+        arc.deserialize_in_place(buffer)
+            .expect("deserialization failed");
+        assert!(arc.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+        assert!(shared.iter().all(|&byte| byte == 0));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
+use alloc::rc::Rc;
+use core::ptr::NonNull;
+use split_array::SplitArray;
+
+impl<T: SerialSize> SerialSize for Rc<T> {
+    const SIZE: usize = <T as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for Rc<T> {
+    const SCHEMA: Layout = T::SCHEMA;
+}
+
+impl<T: Serialize> Serialize for Rc<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        (**self).serialize(head)
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        (**self).try_serialize(head)
+    }
+}
+
+/// Deserialize a `T` directly into a fresh `Rc`, without passing it through the stack.
+fn deserialize_new<T: Deserialize>(
+    buffer: &[u8; <T as SerialSize>::SIZE],
+) -> Result<Rc<T>, T::Error> {
+    let mut value = Rc::<T>::new_uninit();
+    // `value` was just created and is therefore unique
+    let slot = Rc::get_mut(&mut value).unwrap();
+    T::deserialize_into_uninit(slot, buffer)?;
+    // Safety:
+    // `deserialize_into_uninit` just initialized `value`
+    Ok(unsafe { value.assume_init() })
+}
+
+/// The value is deserialized directly into a fresh heap allocation,
+/// so large values never pass through the stack,
+/// even when deserializing in place into a shared `Rc`.
+impl<T: Deserialize> Deserialize for Rc<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = <T as Deserialize>::Error;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        T::validate(head)
+    }
+
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        match Rc::get_mut(self) {
+            | Some(value) => value.deserialize_in_place(head),
+            | None => deserialize_new(head).map(|value| *self = value),
+        }
+    }
+
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        match Rc::get_mut(self) {
+            | Some(value) => value.deserialize_in_place_unchecked(head),
+            | None => deserialize_new(head).map(|value| *self = value),
+        }
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        into.write(deserialize_new(head)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_serial_eq;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn test_rc() {
+        assert_serial_eq!(Rc<u64>, &Rc::new(u64::MAX));
+        assert_serial_eq!(
+            Rc<[Option<bool>; 3]>,
+            &Rc::new([Some(true), None, Some(false)])
+        );
+    }
+}
@@ -1,3 +1,12 @@
 pub mod array;
+pub mod atomic;
+pub mod cell;
+pub mod cmp;
+pub mod marker;
+pub mod mem;
+pub mod net;
+pub mod num;
 pub mod option;
+pub mod range;
+pub mod time;
 pub mod tuple;
@@ -1,9 +1,15 @@
-use crate::{Deserialize, SerialSize, Serialize};
-use core::mem::transmute;
+use crate::impls::serialize_uninit_into;
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
+use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 
 impl<T: SerialSize, const SIZE: usize> SerialSize for [T; SIZE] {
-    const SIZE: usize = SIZE * size_of::<T>();
+    const SIZE: usize = SIZE * <T as SerialSize>::SIZE;
+}
+
+impl<T: Schema, const SIZE: usize> Schema for [T; SIZE] {
+    const SCHEMA: Layout = Layout::array("array", &T::SCHEMA, SIZE);
 }
 
 impl<T: Serialize, const SIZE: usize> Serialize for [T; SIZE]
@@ -12,15 +18,39 @@ where
 {
     #[inline(always)]
     fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
-        if size_of::<T>() == 0 {
-            return;
-        }
-        for (value, buffer) in self
-            .iter()
-            .zip(buffer.array_chunks_mut::<{ <T as SerialSize>::SIZE }>())
-        {
-            value.serialize(buffer)
+        T::serialize_slice(self, buffer)
+    }
+
+    #[inline(always)]
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        T::try_serialize_slice(self, buffer)
+    }
+
+    fn serialize_uninit<'a>(
+        &self,
+        buffer: &'a mut MaybeUninit<[u8; <Self as SerialSize>::SIZE]>,
+    ) -> &'a mut [u8; <Self as SerialSize>::SIZE] {
+        let elements = buffer
+            .as_mut_ptr()
+            .cast::<MaybeUninit<[u8; <T as SerialSize>::SIZE]>>();
+        for (i, value) in self.iter().enumerate() {
+            // Safety:
+            // `buffer` consists of `SIZE` consecutive element buffers,
+            // which have an alignment of 1 and accept any contents
+            serialize_uninit_into(value, unsafe { &mut *elements.add(i) });
         }
+
+        // Safety:
+        // every element buffer was just initialized
+        unsafe { buffer.assume_init_mut() }
+    }
+
+    #[inline(always)]
+    fn serialize_slice(values: &[Self], buffer: &mut [u8]) {
+        T::serialize_slice(values.as_flattened(), buffer)
     }
 }
 
@@ -30,30 +60,178 @@ where
 {
     type Error = T::Error;
 
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), T::Error> {
+        element_buffers::<T, LEN>(buffer).try_for_each(T::validate)
+    }
+
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), T::Error> {
+        // validate all elements first, so that `self` is left untouched on error
+        Self::validate(buffer)?;
+        self.deserialize_in_place_unchecked(buffer)
+    }
+
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), T::Error> {
+        for (value, buffer) in self.iter_mut().zip(element_buffers::<T, LEN>(buffer)) {
+            value.deserialize_in_place_unchecked(buffer)?;
+        }
+
+        Ok(())
+    }
+
     unsafe fn deserialize_raw(
         into: NonNull<[T; LEN]>,
         buffer: &[u8; <Self as SerialSize>::SIZE],
     ) -> Result<(), T::Error> {
         unsafe {
             // Safety:
-            // - `[MaybeUninit<T>; LEN]` has the same layout as `[T; LEN]`
+            // - `[T; LEN]` consists of `LEN` consecutive `T`s
+            // - `buffer` consists of `LEN` consecutive `T` buffers
+            T::deserialize_raw_slice(into.cast(), LEN, buffer)
+        }
+    }
 
-            let into = transmute::<NonNull<[T; LEN]>, NonNull<T>>(into);
+    #[inline(always)]
+    unsafe fn deserialize_raw_slice(
+        into: NonNull<Self>,
+        len: usize,
+        buffer: &[u8],
+    ) -> Result<(), Self::Error> {
+        unsafe {
+            // Safety:
+            // `len` consecutive `[T; LEN]`s are `len * LEN` consecutive `T`s
+            T::deserialize_raw_slice(into.cast(), len * LEN, buffer)
+        }
+    }
+}
 
-            for (i, buffer) in
-                (0..LEN).zip(buffer.array_chunks::<{ <T as SerialSize>::SIZE }>())
-            {
-                // Safety:
-                // `i` is bounded by LEN and therefore never exceeds the allocation
-                <T as Deserialize>::deserialize_raw(into.add(i), buffer)?;
-            }
-        };
+/// Split the `buffer` of an array into the `LEN` buffers of its elements.
+fn element_buffers<T: SerialSize, const LEN: usize>(
+    buffer: &[u8; <[T; LEN] as SerialSize>::SIZE],
+) -> impl Iterator<Item = &[u8; <T as SerialSize>::SIZE]>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    // `array_chunks` does not support empty chunks
+    let chunks = (<T as SerialSize>::SIZE != 0)
+        .then(|| buffer.array_chunks::<{ <T as SerialSize>::SIZE }>());
+    let empty = (<T as SerialSize>::SIZE == 0)
+        .then(|| <&[u8; <T as SerialSize>::SIZE]>::try_from(&buffer[..0]).unwrap());
 
-        Ok(())
-    }
+    chunks.into_iter().flatten().chain(
+        empty
+            .into_iter()
+            .flat_map(|empty| core::iter::repeat(empty).take(LEN)),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     //! see [`crate::impls::core::tuple::tests::test_complex`] for an array (de-)serialisation test
+
+    use crate::impls::core::option::DeserializeOptionError;
+    use crate::{Deserialize, SerialSize, Serialize};
+
+    #[test]
+    fn test_size() {
+        assert_eq!(0, <[u32; 0] as SerialSize>::SIZE);
+        assert_eq!(3 * 4, <[char; 3] as SerialSize>::SIZE);
+        assert_eq!(
+            2 * (1 + 9 + 2),
+            <[(bool, Option<f64>, i16); 2] as SerialSize>::SIZE
+        );
+    }
+
+    #[test]
+    fn test_bulk() {
+        let value = [[0x0102u16, 0x0304, 0x0506], [0x0708, 0x090a, 0x0b0c]];
+        let mut buf = [0; <[[u16; 3]; 2] as SerialSize>::SIZE];
+        value.serialize(&mut buf);
+
+        for (x, chunk) in value.as_flattened().iter().zip(buf.array_chunks()) {
+            let mut expected = [0; <u16 as SerialSize>::SIZE];
+            x.serialize(&mut expected);
+            assert_eq!(&expected, chunk);
+        }
+        assert_eq!(Ok(value), <[[u16; 3]; 2]>::deserialize(&buf));
+
+        let value = [1.5f32, -0.0, f32::INFINITY, f32::MIN_POSITIVE];
+        let mut buf = [0; <[f32; 4] as SerialSize>::SIZE];
+        value.serialize(&mut buf);
+        let de = <[f32; 4]>::deserialize(&buf).unwrap();
+        assert_eq!(value.map(f32::to_bits), de.map(f32::to_bits));
+    }
+
+    #[test]
+    fn test_in_place() {
+        let mut value = [Some(1u16), None, Some(3)];
+        let mut buf = [0; <[Option<u16>; 3] as SerialSize>::SIZE];
+        [None, Some(5u16), Some(6)].serialize(&mut buf);
+
+        value
+            .deserialize_in_place(&buf)
+            .expect("deserialization failed");
+        assert_eq!([None, Some(5), Some(6)], value);
+
+        // an illegal tag in the last element
+        buf[6] = 2;
+        assert_eq!(
+            Err(DeserializeOptionError::IllegalTag),
+            value.deserialize_in_place(&buf)
+        );
+        assert_eq!([None, Some(5), Some(6)], value);
+    }
+
+    #[test]
+    fn test_in_place_validates_once() {
+        use core::ptr::NonNull;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static VALIDATIONS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug, PartialEq)]
+        struct Counted(u8);
+
+        impl SerialSize for Counted {
+            const SIZE: usize = 1;
+        }
+
+        impl Deserialize for Counted {
+            type Error = core::convert::Infallible;
+
+            fn validate(_buffer: &[u8; 1]) -> Result<(), Self::Error> {
+                VALIDATIONS.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; 1],
+            ) -> Result<(), Self::Error> {
+                into.write(Counted(buffer[0]));
+                Ok(())
+            }
+        }
+
+        let mut value = [
+            [(Counted(0),), (Counted(0),)],
+            [(Counted(0),), (Counted(0),)],
+        ];
+        value
+            .deserialize_in_place(&[1, 2, 3, 4])
+            .expect("deserialization failed");
+        assert_eq!(
+            [
+                [(Counted(1),), (Counted(2),)],
+                [(Counted(3),), (Counted(4),)]
+            ],
+            value
+        );
+        assert_eq!(4, VALIDATIONS.load(Ordering::Relaxed));
+    }
 }
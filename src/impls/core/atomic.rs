@@ -0,0 +1,250 @@
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize};
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+use split_array::SplitArray;
+
+/// An atomic whose value can be serialized with a caller-chosen memory ordering.
+///
+/// [`Serialize::serialize`] loads with [`Ordering::SeqCst`].
+/// To pick a weaker ordering per field, wrap the atomic in [`Relaxed`] or [`Acquire`].
+pub trait SerializeAtomic: Serialize {
+    /// Serialize `self` into the provided `buffer`, loading the value with `ordering`.
+    ///
+    /// # Panics
+    /// Panics if `ordering` is [`Ordering::Release`] or [`Ordering::AcqRel`].
+    fn serialize_with_ordering(&self, buffer: &mut [u8; Self::SIZE], ordering: Ordering);
+}
+
+macro_rules! impl_ordering_wrapper {
+    ($(#[$attr:meta])* $wrapper:ident, $ordering:expr) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        #[derive(Debug, Default)]
+        pub struct $wrapper<A>(pub A);
+
+        impl<A> $wrapper<A> {
+            /// View a reference to an atomic as a reference to this wrapper.
+            pub fn from_ref(atomic: &A) -> &Self {
+                // Safety:
+                // `Self` is `repr(transparent)`
+                unsafe { &*(atomic as *const A).cast::<Self>() }
+            }
+
+            /// Unwrap the atomic.
+            pub fn into_inner(self) -> A {
+                self.0
+            }
+        }
+
+        impl<A: SerialSize> SerialSize for $wrapper<A> {
+            const SIZE: usize = <A as SerialSize>::SIZE;
+        }
+
+        impl<A: Schema> Schema for $wrapper<A> {
+            const SCHEMA: Layout = A::SCHEMA;
+        }
+
+        impl<A: SerializeAtomic> Serialize for $wrapper<A>
+        where
+            [(); <Self as SerialSize>::SIZE - <A as SerialSize>::SIZE]:,
+        {
+            fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                let (head, tail) = buffer.split_arr_mut();
+                debug_assert_eq!(0, tail.len());
+                self.0.serialize_with_ordering(head, $ordering)
+            }
+        }
+
+        impl<A: Deserialize> Deserialize for $wrapper<A>
+        where
+            [(); <Self as SerialSize>::SIZE - <A as SerialSize>::SIZE]:,
+        {
+            type Error = <A as Deserialize>::Error;
+
+            fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                A::validate(head)
+            }
+
+            fn deserialize_in_place(
+                &mut self,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                self.0.deserialize_in_place(head)
+            }
+
+            fn deserialize_in_place_unchecked(
+                &mut self,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                self.0.deserialize_in_place_unchecked(head)
+            }
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                // Safety:
+                // `Self` is `repr(transparent)`
+                unsafe { A::deserialize_raw(into.cast(), head) }
+            }
+        }
+    };
+}
+
+impl_ordering_wrapper!(
+    /// An atomic that is serialized with a [`Ordering::Relaxed`] load.
+    Relaxed,
+    Ordering::Relaxed
+);
+impl_ordering_wrapper!(
+    /// An atomic that is serialized with an [`Ordering::Acquire`] load.
+    Acquire,
+    Ordering::Acquire
+);
+
+#[cfg(target_has_atomic_load_store = "ptr")]
+pub use ptr::PtrAddr;
+
+#[cfg(target_has_atomic_load_store = "ptr")]
+mod ptr {
+    use super::SerializeAtomic;
+    use crate::schema::{Layout, Schema};
+    use crate::{Deserialize, SerialSize, Serialize};
+    use core::ptr::NonNull;
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    /// An [`AtomicPtr`] that is serialized as the `usize` address it points to.
+    ///
+    /// Deserialized pointers carry the address only. They are not derived from
+    /// any allocation of this process and are only meant for inspection,
+    /// e.g. in debug snapshots. Never dereference them unless you otherwise
+    /// know them to be valid.
+    #[repr(transparent)]
+    #[derive(Debug, Default)]
+    pub struct PtrAddr<T>(AtomicPtr<T>);
+
+    impl<T> PtrAddr<T> {
+        /// Opt into serializing the address stored in `ptr`.
+        ///
+        /// # Safety
+        /// Serializing exposes the addresses of `ptr`'s targets.
+        /// Deserialized pointers must never be dereferenced unless
+        /// the caller otherwise knows them to be valid.
+        pub unsafe fn new(ptr: AtomicPtr<T>) -> Self {
+            Self(ptr)
+        }
+
+        /// View a reference to an [`AtomicPtr`] as a reference to a `PtrAddr`.
+        ///
+        /// # Safety
+        /// See [`PtrAddr::new`].
+        pub unsafe fn from_ref(ptr: &AtomicPtr<T>) -> &Self {
+            // Safety:
+            // `Self` is `repr(transparent)`
+            unsafe { &*(ptr as *const AtomicPtr<T>).cast::<Self>() }
+        }
+
+        /// Unwrap the [`AtomicPtr`].
+        pub fn into_inner(self) -> AtomicPtr<T> {
+            self.0
+        }
+    }
+
+    impl<T> SerialSize for PtrAddr<T> {
+        const SIZE: usize = <usize as SerialSize>::SIZE;
+    }
+
+    impl<T> Schema for PtrAddr<T> {
+        const SCHEMA: Layout = usize::SCHEMA;
+    }
+
+    impl<T> Serialize for PtrAddr<T> {
+        fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+            self.serialize_with_ordering(buffer, Ordering::SeqCst)
+        }
+    }
+
+    impl<T> SerializeAtomic for PtrAddr<T> {
+        fn serialize_with_ordering(
+            &self,
+            buffer: &mut [u8; <Self as SerialSize>::SIZE],
+            ordering: Ordering,
+        ) {
+            (self.0.load(ordering) as usize).serialize(buffer)
+        }
+    }
+
+    impl<T> Deserialize for PtrAddr<T> {
+        type Error = core::convert::Infallible;
+
+        unsafe fn deserialize_raw(
+            into: NonNull<Self>,
+            buffer: &[u8; <Self as SerialSize>::SIZE],
+        ) -> Result<(), Self::Error> {
+            let addr = usize::deserialize(buffer)?;
+            into.write(Self(AtomicPtr::new(addr as *mut T)));
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Acquire, Relaxed, SerializeAtomic};
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+    use core::sync::atomic::*;
+
+    #[test]
+    #[cfg(target_has_atomic_load_store = "32")]
+    fn test_wrappers() {
+        let load = |atomic: &Relaxed<AtomicU32>| atomic.0.load(Ordering::SeqCst);
+        assert_serial_eq!(Relaxed<AtomicU32>, &Relaxed(AtomicU32::new(0x1234)), load);
+
+        let load = |atomic: &Acquire<AtomicI16>| atomic.0.load(Ordering::SeqCst);
+        assert_serial_eq!(Acquire<AtomicI16>, &Acquire(AtomicI16::new(-3)), load);
+
+        let counter = AtomicU32::new(17);
+        let mut buf = [0; <AtomicU32 as SerialSize>::SIZE];
+        Relaxed::from_ref(&counter).serialize(&mut buf);
+        assert_eq!(Ok(17), u32::deserialize(&buf));
+    }
+
+    #[test]
+    #[cfg(target_has_atomic_load_store = "8")]
+    fn test_with_ordering() {
+        let flag = AtomicBool::new(true);
+        let mut seq_cst = [0; <AtomicBool as SerialSize>::SIZE];
+        let mut relaxed = [0; <AtomicBool as SerialSize>::SIZE];
+        flag.serialize(&mut seq_cst);
+        flag.serialize_with_ordering(&mut relaxed, Ordering::Relaxed);
+        assert_eq!(seq_cst, relaxed);
+    }
+
+    #[test]
+    #[cfg(target_has_atomic_load_store = "ptr")]
+    fn test_ptr_addr() {
+        use super::PtrAddr;
+
+        let mut value = 5u64;
+        // Safety:
+        // the deserialized pointer is only compared, never dereferenced
+        let ptr = unsafe { PtrAddr::new(AtomicPtr::new(&raw mut value)) };
+        let mut buf = [0; <PtrAddr<u64> as SerialSize>::SIZE];
+        Acquire::from_ref(&ptr).serialize(&mut buf);
+
+        let de = PtrAddr::<u64>::deserialize(&buf).expect("deserialization failed");
+        assert_eq!(
+            &raw mut value as usize,
+            de.into_inner().load(Ordering::SeqCst) as usize
+        );
+    }
+}
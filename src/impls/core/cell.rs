@@ -0,0 +1,203 @@
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
+use core::cell::{Cell, RefCell};
+use core::ptr::NonNull;
+use split_array::SplitArray;
+
+impl<T: SerialSize> SerialSize for Cell<T> {
+    const SIZE: usize = <T as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for Cell<T> {
+    const SCHEMA: Layout = T::SCHEMA;
+}
+
+impl<T: Serialize + Copy> Serialize for Cell<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.get().serialize(head)
+    }
+}
+
+impl<T: Deserialize> Deserialize for Cell<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = <T as Deserialize>::Error;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        T::validate(head)
+    }
+
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        self.get_mut().deserialize_in_place(head)
+    }
+
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        self.get_mut().deserialize_in_place_unchecked(head)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        // Safety:
+        // `Cell<T>` has the same in-memory representation as `T`
+        unsafe { T::deserialize_raw(into.cast(), head) }
+    }
+}
+
+impl<T: SerialSize> SerialSize for RefCell<T> {
+    const SIZE: usize = <T as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for RefCell<T> {
+    const SCHEMA: Layout = T::SCHEMA;
+}
+
+/// [`serialize`](Serialize::serialize) panics if the value is currently mutably borrowed,
+/// like [`RefCell::borrow`], while [`try_serialize`](Serialize::try_serialize)
+/// returns [`SerializeError::Borrowed`].
+impl<T: Serialize> Serialize for RefCell<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.borrow().serialize(head)
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.try_borrow()
+            .map_err(|_| SerializeError::Borrowed)?
+            .try_serialize(head)
+    }
+}
+
+impl<T: Deserialize> Deserialize for RefCell<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = <T as Deserialize>::Error;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        T::validate(head)
+    }
+
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        self.get_mut().deserialize_in_place(head)
+    }
+
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        self.get_mut().deserialize_in_place_unchecked(head)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        let value = T::deserialize(head)?;
+        unsafe {
+            // Safety:
+            // `into` is valid for writes
+            into.write(RefCell::new(value));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize, SerializeError};
+    use core::cell::{Cell, RefCell};
+
+    #[test]
+    fn test_cell() {
+        let get = |cell: &Cell<i32>| cell.get();
+        assert_serial_eq!(Cell<i32>, &Cell::new(-12), get);
+        assert_serial_eq!(Cell<i32>, &Cell::new(i32::MAX), get);
+        assert_serial_eq!(
+            [Cell<bool>; 2],
+            &[Cell::new(true), Cell::new(false)],
+            |cells: &[Cell<bool>; 2]| cells.clone().map(Cell::into_inner)
+        );
+    }
+
+    #[test]
+    fn test_ref_cell() {
+        let cell = RefCell::new([1u16, 2, 3]);
+        let mut buf = [0; <RefCell<[u16; 3]> as SerialSize>::SIZE];
+        cell.serialize(&mut buf);
+
+        let de = RefCell::<[u16; 3]>::deserialize(&buf).expect("deserialization failed");
+        assert_eq!(cell, de);
+
+        let nested = RefCell::new(RefCell::new('a'));
+        assert_serial_eq!(RefCell<RefCell<char>>, &nested);
+        assert_serial_eq!(
+            (RefCell<u8>, [RefCell<bool>; 2]),
+            &(RefCell::new(3), [RefCell::new(true), RefCell::new(false)])
+        );
+    }
+
+    #[test]
+    fn test_ref_cell_borrowed() {
+        let cell = RefCell::new(5u8);
+        let mut buf = [0; <RefCell<u8> as SerialSize>::SIZE];
+
+        let borrow = cell.borrow();
+        assert_eq!(Ok(()), cell.try_serialize(&mut buf));
+
+        drop(borrow);
+        let borrow = cell.borrow_mut();
+        assert_eq!(Err(SerializeError::Borrowed), cell.try_serialize(&mut buf));
+        drop(borrow);
+
+        let nested = Some((1u8, [RefCell::new(()), RefCell::new(())]));
+        let borrow = nested.as_ref().unwrap().1[1].borrow_mut();
+        assert_eq!(
+            Err(SerializeError::Borrowed),
+            nested.try_serialize(&mut [0; 2])
+        );
+        drop(borrow);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::impls::IllegalBitPattern;
+use crate::schema::{Layout, Primitive, Schema, Variant};
+use crate::{Deserialize, SerialSize, Serialize};
+use core::cmp::{Ordering, Reverse};
+use core::ptr::NonNull;
+
+impl_for_transparent!(Reverse, value => &value.0);
+
+/// An [`Ordering`] is serialized as an `i8` holding `-1`, `0` or `1`.
+impl SerialSize for Ordering {
+    const SIZE: usize = <i8 as SerialSize>::SIZE;
+}
+
+impl Schema for Ordering {
+    const SCHEMA: Layout = Layout::enumeration(
+        "Ordering",
+        <i8 as SerialSize>::SIZE,
+        &Layout::primitive("i8", <i8 as SerialSize>::SIZE, Primitive::Signed),
+        &[
+            Variant {
+                name: "Less",
+                tag: -1,
+                fields: &[],
+            },
+            Variant {
+                name: "Equal",
+                tag: 0,
+                fields: &[],
+            },
+            Variant {
+                name: "Greater",
+                tag: 1,
+                fields: &[],
+            },
+        ],
+        true,
+    );
+}
+
+impl Serialize for Ordering {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        (*self as i8).serialize(buffer)
+    }
+}
+
+impl Deserialize for Ordering {
+    type Error = IllegalBitPattern;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        match i8::deserialize(buffer).unwrap() {
+            | value if (-1..=1).contains(&value) => Ok(()),
+            | _ => Err(IllegalBitPattern),
+        }
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(match i8::deserialize(buffer).unwrap() {
+            | -1 => Ordering::Less,
+            | 0 => Ordering::Equal,
+            | 1 => Ordering::Greater,
+            | _ => Err(IllegalBitPattern)?,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::impls::IllegalBitPattern;
+    use crate::{assert_serial_eq, Deserialize};
+    use core::cmp::{Ordering, Reverse};
+
+    #[test]
+    fn test_reverse() {
+        assert_serial_eq!(Reverse<u32>, &Reverse(7));
+        assert_serial_eq!(Reverse<(bool, i16)>, &Reverse((true, -300)));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert_serial_eq!(Ordering, &Ordering::Less);
+        assert_serial_eq!(Ordering, &Ordering::Equal);
+        assert_serial_eq!(Ordering, &Ordering::Greater);
+
+        assert_eq!(Err(IllegalBitPattern), Ordering::deserialize(&[2]));
+        assert_eq!(Err(IllegalBitPattern), Ordering::deserialize(&[0x80]));
+        assert_eq!(Err(IllegalBitPattern), Ordering::validate(&[2]));
+        assert_eq!(Ok(()), Ordering::validate(&[0xff]));
+    }
+}
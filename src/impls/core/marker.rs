@@ -0,0 +1,91 @@
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize};
+use core::marker::{PhantomData, PhantomPinned};
+use core::ptr::NonNull;
+
+impl<T: ?Sized> SerialSize for PhantomData<T> {
+    const SIZE: usize = 0;
+}
+
+impl<T: ?Sized> Schema for PhantomData<T> {
+    const SCHEMA: Layout = Layout::structure("PhantomData", &[]);
+}
+
+impl<T: ?Sized> Serialize for PhantomData<T> {
+    fn serialize(&self, _buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+}
+
+impl<T: ?Sized> Deserialize for PhantomData<T> {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        _buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(PhantomData);
+        Ok(())
+    }
+}
+
+impl SerialSize for PhantomPinned {
+    const SIZE: usize = 0;
+}
+
+impl Schema for PhantomPinned {
+    const SCHEMA: Layout = Layout::structure("PhantomPinned", &[]);
+}
+
+impl Serialize for PhantomPinned {
+    fn serialize(&self, _buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+}
+
+impl Deserialize for PhantomPinned {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        _buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(PhantomPinned);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_serial_eq, SerialSize};
+    use core::marker::{PhantomData, PhantomPinned};
+
+    #[test]
+    fn test_zero_sized() {
+        assert_eq!(0, <PhantomData<[u64; 4]> as SerialSize>::SIZE);
+        assert_eq!(0, <PhantomPinned as SerialSize>::SIZE);
+        assert_eq!(0, <() as SerialSize>::SIZE);
+        assert_eq!(0, <[(); 16] as SerialSize>::SIZE);
+
+        assert_serial_eq!(PhantomData<str>, &PhantomData);
+        assert_serial_eq!(PhantomPinned, &PhantomPinned);
+        assert_serial_eq!((), &());
+    }
+
+    #[test]
+    fn test_zero_sized_in_compounds() {
+        assert_serial_eq!([(); 3], &[(); 3]);
+        assert_serial_eq!([PhantomData<u8>; 4], &[PhantomData; 4]);
+        assert_serial_eq!([[(); 2]; 2], &[[(); 2]; 2]);
+        assert_serial_eq!(
+            ((), u16, PhantomData<char>, [(); 5], bool),
+            &((), 0x1234, PhantomData, [(); 5], true)
+        );
+        assert_serial_eq!(Option<()>, &Some(()));
+        assert_serial_eq!(Option<PhantomPinned>, &None);
+    }
+}
@@ -0,0 +1,63 @@
+use core::mem::ManuallyDrop;
+
+// Deserializing in place must not drop the replaced value.
+impl_for_transparent!(ManuallyDrop, value => &**value, leak);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_serial_eq, Deserialize, SerialSize};
+    use core::mem::ManuallyDrop;
+
+    #[test]
+    fn test_manually_drop() {
+        assert_serial_eq!(ManuallyDrop<u64>, &ManuallyDrop::new(1234));
+        assert_serial_eq!(ManuallyDrop<Option<char>>, &ManuallyDrop::new(Some('x')));
+    }
+
+    #[test]
+    fn test_in_place_leaks() {
+        use core::ptr::NonNull;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counted(u8);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        impl SerialSize for Counted {
+            const SIZE: usize = 1;
+        }
+
+        impl Deserialize for Counted {
+            type Error = core::convert::Infallible;
+
+            fn validate(_buffer: &[u8; 1]) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; 1],
+            ) -> Result<(), Self::Error> {
+                into.write(Counted(buffer[0]));
+                Ok(())
+            }
+        }
+
+        let mut value = ManuallyDrop::new(Counted(0));
+        value
+            .deserialize_in_place(&[1])
+            .expect("deserialization failed");
+        assert_eq!(1, value.0);
+        value
+            .deserialize_in_place_unchecked(&[2])
+            .expect("deserialization failed");
+        assert_eq!(2, value.0);
+        assert_eq!(0, DROPS.load(Ordering::Relaxed));
+    }
+}
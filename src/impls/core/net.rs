@@ -0,0 +1,407 @@
+//! Network addresses are always serialized in network byte order,
+//! regardless of the `primitive_le` and `primitive_be` features.
+//!
+//! [`IpAddr`] and [`SocketAddr`] are prefixed with a one byte tag
+//! (`0` for V4, `1` for V6) and sized to fit their V6 variant.
+//! The unused trailing bytes of a V4 address must be zero.
+
+use crate::impls::IllegalBitPattern;
+use crate::schema::{sequence, Endian, Layout, Primitive, Schema, Variant};
+use crate::{Deserialize, SerialSize, Serialize};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::ptr::NonNull;
+use split_array::SplitArray;
+
+const TAG_V4: u8 = 0;
+const TAG_V6: u8 = 1;
+
+/// Ports, flow labels and scope ids in network byte order.
+const U16_BE: Layout =
+    Layout::primitive_with_endian("u16", 2, Primitive::Unsigned, Endian::Big);
+const U32_BE: Layout =
+    Layout::primitive_with_endian("u32", 4, Primitive::Unsigned, Endian::Big);
+
+impl SerialSize for Ipv4Addr {
+    const SIZE: usize = 4;
+}
+
+impl Schema for Ipv4Addr {
+    const SCHEMA: Layout = Layout::array("Ipv4Addr", &u8::SCHEMA, 4);
+}
+
+impl Serialize for Ipv4Addr {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        *buffer = self.octets();
+    }
+}
+
+impl Deserialize for Ipv4Addr {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(Ipv4Addr::from(*buffer));
+        Ok(())
+    }
+}
+
+impl SerialSize for Ipv6Addr {
+    const SIZE: usize = 16;
+}
+
+impl Schema for Ipv6Addr {
+    const SCHEMA: Layout = Layout::array("Ipv6Addr", &u8::SCHEMA, 16);
+}
+
+impl Serialize for Ipv6Addr {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        *buffer = self.octets();
+    }
+}
+
+impl Deserialize for Ipv6Addr {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(Ipv6Addr::from(*buffer));
+        Ok(())
+    }
+}
+
+impl SerialSize for IpAddr {
+    const SIZE: usize = <u8 as SerialSize>::SIZE + <Ipv6Addr as SerialSize>::SIZE;
+}
+
+impl Schema for IpAddr {
+    const SCHEMA: Layout = Layout::enumeration(
+        "IpAddr",
+        <Self as SerialSize>::SIZE,
+        &u8::SCHEMA,
+        &[
+            Variant {
+                name: "V4",
+                tag: TAG_V4 as i64,
+                fields: &sequence(<u8 as SerialSize>::SIZE, [("0", &Ipv4Addr::SCHEMA)]),
+            },
+            Variant {
+                name: "V6",
+                tag: TAG_V6 as i64,
+                fields: &sequence(<u8 as SerialSize>::SIZE, [("0", &Ipv6Addr::SCHEMA)]),
+            },
+        ],
+        true,
+    );
+}
+
+impl Serialize for IpAddr {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (tag, buffer) = buffer.split_arr_mut();
+        match self {
+            | IpAddr::V4(addr) => {
+                *tag = [TAG_V4];
+                let (addr_buffer, padding) = buffer.split_arr_mut();
+                addr.serialize(addr_buffer);
+                padding.fill(0);
+            }
+            | IpAddr::V6(addr) => {
+                *tag = [TAG_V6];
+                addr.serialize(buffer);
+            }
+        }
+    }
+}
+
+impl Deserialize for IpAddr {
+    type Error = IllegalBitPattern;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (tag, buffer) = buffer.split_arr();
+        let (_, padding): (&[u8; <Ipv4Addr as SerialSize>::SIZE], _) = buffer.split_arr();
+        match *tag {
+            | [TAG_V4] if padding.iter().all(|&byte| byte == 0) => Ok(()),
+            | [TAG_V6] => Ok(()),
+            | _ => Err(IllegalBitPattern),
+        }
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        Self::validate(buffer)?;
+        let (tag, buffer) = buffer.split_arr();
+        let addr = match *tag {
+            | [TAG_V4] => {
+                IpAddr::V4(Ipv4Addr::deserialize(buffer.split_arr().0).unwrap())
+            }
+            | _ => IpAddr::V6(Ipv6Addr::deserialize(buffer).unwrap()),
+        };
+
+        into.write(addr);
+        Ok(())
+    }
+}
+
+impl SerialSize for SocketAddrV4 {
+    const SIZE: usize = <Ipv4Addr as SerialSize>::SIZE + <u16 as SerialSize>::SIZE;
+}
+
+impl Schema for SocketAddrV4 {
+    const SCHEMA: Layout = Layout::structure(
+        "SocketAddrV4",
+        &sequence(0, [("ip", &Ipv4Addr::SCHEMA), ("port", &U16_BE)]),
+    );
+}
+
+impl Serialize for SocketAddrV4 {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (ip, port) = buffer.split_arr_mut();
+        self.ip().serialize(ip);
+        *port = self.port().to_be_bytes();
+    }
+}
+
+impl Deserialize for SocketAddrV4 {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (ip, port) = buffer.split_arr();
+        into.write(SocketAddrV4::new(
+            Ipv4Addr::deserialize(ip)?,
+            u16::from_be_bytes(*port),
+        ));
+        Ok(())
+    }
+}
+
+impl SerialSize for SocketAddrV6 {
+    const SIZE: usize = <Ipv6Addr as SerialSize>::SIZE
+        + <u16 as SerialSize>::SIZE
+        + <u32 as SerialSize>::SIZE
+        + <u32 as SerialSize>::SIZE;
+}
+
+impl Schema for SocketAddrV6 {
+    const SCHEMA: Layout = Layout::structure(
+        "SocketAddrV6",
+        &sequence(
+            0,
+            [
+                ("ip", &Ipv6Addr::SCHEMA),
+                ("port", &U16_BE),
+                ("flowinfo", &U32_BE),
+                ("scope_id", &U32_BE),
+            ],
+        ),
+    );
+}
+
+impl Serialize for SocketAddrV6 {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (ip, buffer) = buffer.split_arr_mut();
+        let (port, buffer) = buffer.split_arr_mut();
+        let (flowinfo, scope_id) = buffer.split_arr_mut();
+        self.ip().serialize(ip);
+        *port = self.port().to_be_bytes();
+        *flowinfo = self.flowinfo().to_be_bytes();
+        *scope_id = self.scope_id().to_be_bytes();
+    }
+}
+
+impl Deserialize for SocketAddrV6 {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (ip, buffer) = buffer.split_arr();
+        let (port, buffer) = buffer.split_arr();
+        let (flowinfo, scope_id) = buffer.split_arr();
+        into.write(SocketAddrV6::new(
+            Ipv6Addr::deserialize(ip)?,
+            u16::from_be_bytes(*port),
+            u32::from_be_bytes(*flowinfo),
+            u32::from_be_bytes(*scope_id),
+        ));
+        Ok(())
+    }
+}
+
+impl SerialSize for SocketAddr {
+    const SIZE: usize = <u8 as SerialSize>::SIZE + <SocketAddrV6 as SerialSize>::SIZE;
+}
+
+impl Schema for SocketAddr {
+    const SCHEMA: Layout = Layout::enumeration(
+        "SocketAddr",
+        <Self as SerialSize>::SIZE,
+        &u8::SCHEMA,
+        &[
+            Variant {
+                name: "V4",
+                tag: TAG_V4 as i64,
+                fields: &sequence(
+                    <u8 as SerialSize>::SIZE,
+                    [("0", &SocketAddrV4::SCHEMA)],
+                ),
+            },
+            Variant {
+                name: "V6",
+                tag: TAG_V6 as i64,
+                fields: &sequence(
+                    <u8 as SerialSize>::SIZE,
+                    [("0", &SocketAddrV6::SCHEMA)],
+                ),
+            },
+        ],
+        true,
+    );
+}
+
+impl Serialize for SocketAddr {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (tag, buffer) = buffer.split_arr_mut();
+        match self {
+            | SocketAddr::V4(addr) => {
+                *tag = [TAG_V4];
+                let (addr_buffer, padding) = buffer.split_arr_mut();
+                addr.serialize(addr_buffer);
+                padding.fill(0);
+            }
+            | SocketAddr::V6(addr) => {
+                *tag = [TAG_V6];
+                addr.serialize(buffer);
+            }
+        }
+    }
+}
+
+impl Deserialize for SocketAddr {
+    type Error = IllegalBitPattern;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (tag, buffer) = buffer.split_arr();
+        let (_, padding): (&[u8; <SocketAddrV4 as SerialSize>::SIZE], _) =
+            buffer.split_arr();
+        match *tag {
+            | [TAG_V4] if padding.iter().all(|&byte| byte == 0) => Ok(()),
+            | [TAG_V6] => Ok(()),
+            | _ => Err(IllegalBitPattern),
+        }
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        Self::validate(buffer)?;
+        let (tag, buffer) = buffer.split_arr();
+        let addr = match *tag {
+            | [TAG_V4] => {
+                SocketAddr::V4(SocketAddrV4::deserialize(buffer.split_arr().0).unwrap())
+            }
+            | _ => SocketAddr::V6(SocketAddrV6::deserialize(buffer).unwrap()),
+        };
+
+        into.write(addr);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::impls::IllegalBitPattern;
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+    use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    const V4: Ipv4Addr = Ipv4Addr::new(192, 168, 10, 1);
+    const V6: Ipv6Addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0x12, 0x34, 0x56, 0x78);
+
+    #[test]
+    fn test_ip() {
+        assert_serial_eq!(Ipv4Addr, &V4);
+        assert_serial_eq!(Ipv4Addr, &Ipv4Addr::UNSPECIFIED);
+        assert_serial_eq!(Ipv6Addr, &V6);
+        assert_serial_eq!(Ipv6Addr, &Ipv6Addr::LOCALHOST);
+        assert_serial_eq!(IpAddr, &IpAddr::V4(V4));
+        assert_serial_eq!(IpAddr, &IpAddr::V6(V6));
+    }
+
+    #[test]
+    fn test_socket() {
+        let v4 = SocketAddrV4::new(V4, 8080);
+        let v6 = SocketAddrV6::new(V6, 443, 0x000abcde, 7);
+
+        assert_serial_eq!(SocketAddrV4, &v4);
+        assert_serial_eq!(SocketAddrV6, &v6);
+        assert_serial_eq!(SocketAddr, &SocketAddr::V4(v4));
+        assert_serial_eq!(SocketAddr, &SocketAddr::V6(v6));
+    }
+
+    #[test]
+    fn test_network_byte_order() {
+        let mut buf = [0; <SocketAddrV6 as SerialSize>::SIZE];
+        SocketAddrV6::new(V6, 0x1234, 0x000abcde, 0x01020304).serialize(&mut buf);
+
+        assert_eq!(V6.octets(), buf[..16]);
+        assert_eq!([0x12, 0x34], buf[16..18]);
+        assert_eq!([0x00, 0x0a, 0xbc, 0xde], buf[18..22]);
+        assert_eq!([0x01, 0x02, 0x03, 0x04], buf[22..26]);
+
+        let mut buf = [0xff; <SocketAddr as SerialSize>::SIZE];
+        SocketAddr::V4(SocketAddrV4::new(V4, 0x5678)).serialize(&mut buf);
+
+        assert_eq!([0, 192, 168, 10, 1, 0x56, 0x78], buf[..7]);
+        assert!(buf[7..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_illegal() {
+        let mut buf = [0; <IpAddr as SerialSize>::SIZE];
+        IpAddr::V4(V4).serialize(&mut buf);
+        buf[0] = 2;
+        assert_eq!(Err(IllegalBitPattern), IpAddr::deserialize(&buf));
+
+        assert_eq!(Err(IllegalBitPattern), IpAddr::validate(&buf));
+
+        buf[0] = 0;
+        buf[16] = 1;
+        assert_eq!(Err(IllegalBitPattern), IpAddr::deserialize(&buf));
+        assert_eq!(Err(IllegalBitPattern), IpAddr::validate(&buf));
+        buf[0] = 1;
+        assert_eq!(Ok(()), IpAddr::validate(&buf));
+
+        let mut buf = [0; <SocketAddr as SerialSize>::SIZE];
+        buf[0] = 0xff;
+        assert_eq!(Err(IllegalBitPattern), SocketAddr::deserialize(&buf));
+        assert_eq!(Err(IllegalBitPattern), SocketAddr::validate(&buf));
+        buf[0] = 0;
+        buf[26] = 1;
+        assert_eq!(Err(IllegalBitPattern), SocketAddr::validate(&buf));
+    }
+}
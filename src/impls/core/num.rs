@@ -0,0 +1,19 @@
+use core::num::{Saturating, Wrapping};
+
+impl_for_transparent!(Wrapping, value => &value.0);
+impl_for_transparent!(Saturating, value => &value.0);
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_serial_eq;
+    use core::num::{Saturating, Wrapping};
+
+    #[test]
+    fn test_wrappers() {
+        assert_serial_eq!(Wrapping<u32>, &Wrapping(0x12345678));
+        assert_serial_eq!(Wrapping<i8>, &Wrapping(-1));
+        assert_serial_eq!(Saturating<u16>, &Saturating(u16::MAX));
+        assert_serial_eq!(Saturating<Wrapping<i64>>, &Saturating(Wrapping(-4)));
+        assert_serial_eq!([Wrapping<bool>; 2], &[Wrapping(true), Wrapping(false)]);
+    }
+}
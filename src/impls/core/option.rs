@@ -1,6 +1,8 @@
-use crate::impls::IllegalBitPattern;
-use crate::{Deserialize, SerialSize, Serialize};
+use crate::impls::{serialize_uninit_into, split_uninit_mut, IllegalBitPattern};
+use crate::schema::{sequence, Layout, Schema, Variant};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
 use core::fmt::Debug;
+use core::mem::{size_of_val, MaybeUninit};
 use core::ptr::NonNull;
 use split_array::SplitArray;
 
@@ -8,6 +10,29 @@ impl<T: SerialSize> SerialSize for Option<T> {
     const SIZE: usize = <bool as SerialSize>::SIZE + <T as SerialSize>::SIZE;
 }
 
+/// An `Option<T>` is an enum with a `bool` tag.
+/// The payload of `None` is zeroed when serialized, but ignored when deserialized.
+impl<T: Schema> Schema for Option<T> {
+    const SCHEMA: Layout = Layout::enumeration(
+        "Option",
+        <Self as SerialSize>::SIZE,
+        &bool::SCHEMA,
+        &[
+            Variant {
+                name: "None",
+                tag: 0,
+                fields: &[],
+            },
+            Variant {
+                name: "Some",
+                tag: 1,
+                fields: &sequence(<bool as SerialSize>::SIZE, [("0", &T::SCHEMA)]),
+            },
+        ],
+        false,
+    );
+}
+
 impl<T: Serialize> Serialize for Option<T>
 where
     [(); <Self as SerialSize>::SIZE - <bool as SerialSize>::SIZE]:,
@@ -18,7 +43,10 @@ where
     fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
         let (head, buffer) = buffer.split_arr_mut();
         match self.as_ref() {
-            | None => false.serialize(head),
+            | None => {
+                false.serialize(head);
+                buffer.fill(0);
+            }
             | Some(data) => {
                 true.serialize(head);
                 let (head, tail) = buffer.split_arr_mut();
@@ -27,6 +55,49 @@ where
             }
         }
     }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        match self.as_ref() {
+            | None => self.serialize(buffer),
+            | Some(data) => {
+                let (head, buffer) = buffer.split_arr_mut();
+                true.serialize(head);
+                let (head, tail) = buffer.split_arr_mut();
+                debug_assert_eq!(0, tail.len());
+                data.try_serialize(head)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn serialize_uninit<'a>(
+        &self,
+        buffer: &'a mut MaybeUninit<[u8; <Self as SerialSize>::SIZE]>,
+    ) -> &'a mut [u8; <Self as SerialSize>::SIZE] {
+        {
+            let (head, buffer) = split_uninit_mut(buffer);
+            let (payload, tail) = split_uninit_mut(buffer);
+            debug_assert_eq!(0, size_of_val(tail));
+            match self.as_ref() {
+                | None => {
+                    serialize_uninit_into(&false, head);
+                    payload.write([0; <T as SerialSize>::SIZE]);
+                }
+                | Some(data) => {
+                    serialize_uninit_into(&true, head);
+                    serialize_uninit_into(data, payload);
+                }
+            }
+        }
+
+        // Safety:
+        // the tag and the payload just initialized the whole buffer
+        unsafe { buffer.assume_init_mut() }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -41,6 +112,11 @@ impl<T> From<IllegalBitPattern> for DeserializeOptionError<T> {
     }
 }
 
+/// If `Option<T>` stores its discriminant in a niche of `T`, as for `Option<Box<T>>`,
+/// the payload is deserialized in place.
+/// Otherwise, as for `Option<[u8; N]>`, it is deserialized into a temporary first,
+/// which needs stack space for one `T`.
+/// Wrap large payloads without a niche in a `Box` to avoid that.
 impl<T: Deserialize> Deserialize for Option<T>
 where
     [(); <Self as SerialSize>::SIZE - <bool as SerialSize>::SIZE]:,
@@ -50,34 +126,139 @@ where
 {
     type Error = DeserializeOptionError<<T as Deserialize>::Error>;
 
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, buffer) = buffer.split_arr();
+
+        if !<bool as Deserialize>::deserialize(head)? {
+            return Ok(());
+        }
+
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        T::validate(head).map_err(DeserializeOptionError::Data)
+    }
+
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, buffer) = buffer.split_arr();
+
+        if !<bool as Deserialize>::deserialize(head)? {
+            *self = None;
+            return Ok(());
+        }
+
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        match self {
+            | Some(value) => value.deserialize_in_place(head),
+            | None => T::deserialize(head).map(|value| *self = Some(value)),
+        }
+        .map_err(DeserializeOptionError::Data)
+    }
+
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, buffer) = buffer.split_arr();
+
+        if !<bool as Deserialize>::deserialize(head)? {
+            *self = None;
+            return Ok(());
+        }
+
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        match self {
+            | Some(value) => value.deserialize_in_place_unchecked(head),
+            | None => T::deserialize(head).map(|value| *self = Some(value)),
+        }
+        .map_err(DeserializeOptionError::Data)
+    }
+
     unsafe fn deserialize_raw(
         into: NonNull<Option<T>>,
         buffer: &[u8; <Self as SerialSize>::SIZE],
     ) -> Result<(), Self::Error> {
         let (head, buffer) = buffer.split_arr();
 
-        let value = if <bool as Deserialize>::deserialize(head)? {
-            let (head, tail) = buffer.split_arr();
+        if !<bool as Deserialize>::deserialize(head)? {
+            unsafe {
+                // Safety:
+                // `into` is valid for writes
+                write_none(into);
+            }
+            return Ok(());
+        }
+
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
 
-            debug_assert_eq!(0, tail.len());
+        if size_of::<Option<T>>() == size_of::<T>() {
+            // The discriminant is stored in a niche of `T`.
+            // `Option::as_mut` hands out a `&mut T` to the payload, through which
+            // any valid `T` may be written without affecting the discriminant.
+            // With no bytes outside of the payload, the payload is at offset 0
+            // and any valid `T` written there is a valid `Some`.
+            // Therefore, the payload can be deserialized in place.
+            return unsafe {
+                // Safety:
+                // `into` is valid for writes of `Option<T>` and, therefore, of `T`
+                T::deserialize_raw(into.cast::<T>(), head)
+                    .map_err(DeserializeOptionError::Data)
+            };
+        }
 
-            Some(T::deserialize(head).map_err(DeserializeOptionError::Data)?)
-        } else {
-            None
-        };
         unsafe {
             // Safety:
             // `into` is valid for writes
-            into.write(value);
+            write_some_via_temporary(into, head).map_err(DeserializeOptionError::Data)
         }
+    }
+}
 
-        Ok(())
+// The following helpers are kept out of `deserialize_raw`,
+// so that the stack space for their temporaries is only reserved when they are called.
+
+/// # Safety
+/// `into` must be valid for writes.
+#[inline(never)]
+unsafe fn write_none<T>(into: NonNull<Option<T>>) {
+    into.write(None);
+}
+
+/// The discriminant of a tagged `Option` cannot be written without a value:
+/// Rust does not expose where or how the tag is stored, nor does it guarantee that
+/// `Option<MaybeUninit<T>>` shares the layout of `Option<T>`.
+/// So the payload goes through a single temporary on the stack.
+///
+/// # Safety
+/// `into` must be valid for writes.
+#[inline(never)]
+unsafe fn write_some_via_temporary<T: Deserialize>(
+    into: NonNull<Option<T>>,
+    buffer: &[u8; <T as SerialSize>::SIZE],
+) -> Result<(), <T as Deserialize>::Error> {
+    let mut value = MaybeUninit::<T>::uninit();
+    T::deserialize_into_uninit(&mut value, buffer)?;
+    unsafe {
+        // Safety:
+        // - `deserialize_into_uninit` just initialized `value`
+        // - `into` is valid for writes
+        into.write(Some(value.assume_init()));
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    #[allow(unused_imports)]
+    use super::DeserializeOptionError;
+    use crate::impls::IllegalBitPattern;
     use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
 
     type Data = [usize; 10];
@@ -122,4 +303,149 @@ mod tests {
         assert_serial_eq!(Option<Option<Data>>, &some_none);
         assert_serial_eq!(Option<Option<Data>>, &some_some);
     }
+
+    #[test]
+    fn test_niche() {
+        use core::num::NonZeroU32;
+
+        assert_serial_eq!(Option<bool>, &Some(false));
+        assert_serial_eq!(Option<NonZeroU32>, &NonZeroU32::new(7));
+        assert_serial_eq!(Option<Option<bool>>, &Some(None));
+        assert_serial_eq!(Option<Option<bool>>, &Some(Some(true)));
+        assert_serial_eq!(Option<[char; 3]>, &Some(['a', 'b', 'c']));
+        assert_serial_eq!(Option<[char; 3]>, &None);
+    }
+
+    #[test]
+    fn test_none_payload() {
+        let mut buf = [0xff; <Option<u32> as SerialSize>::SIZE];
+        None::<u32>.serialize(&mut buf);
+        assert_eq!([0; 5], buf);
+        assert_eq!([0; 5], None::<u32>.to_bytes());
+    }
+
+    #[test]
+    fn test_in_place() {
+        let mut value = Some([1u8, 2, 3]);
+        let mut buf = [0; <Option<[u8; 3]> as SerialSize>::SIZE];
+
+        None::<[u8; 3]>.serialize(&mut buf);
+        value
+            .deserialize_in_place(&buf)
+            .expect("deserialization failed");
+        assert_eq!(None, value);
+
+        Some([4u8, 5, 6]).serialize(&mut buf);
+        value
+            .deserialize_in_place(&buf)
+            .expect("deserialization failed");
+        assert_eq!(Some([4, 5, 6]), value);
+
+        Some([7u8, 8, 9]).serialize(&mut buf);
+        value
+            .deserialize_in_place(&buf)
+            .expect("deserialization failed");
+        assert_eq!(Some([7, 8, 9]), value);
+
+        buf[0] = 2;
+        assert_eq!(
+            Err(DeserializeOptionError::IllegalTag),
+            value.deserialize_in_place(&buf)
+        );
+        assert_eq!(Some([7, 8, 9]), value);
+    }
+
+    #[test]
+    fn test_illegal_payload() {
+        let mut buf = [0; <Option<bool> as SerialSize>::SIZE];
+        Some(true).serialize(&mut buf);
+        buf[1] = 2;
+        assert_eq!(
+            Err(DeserializeOptionError::Data(IllegalBitPattern)),
+            Option::<bool>::deserialize(&buf)
+        );
+    }
+
+    #[test]
+    fn test_stack_usage() {
+        extern crate std;
+
+        use core::mem::MaybeUninit;
+        use std::alloc::{alloc, dealloc, Layout};
+        use std::thread;
+        use std::vec;
+
+        // a niche-optimized payload, much larger than the stack below
+        const LEN: usize = 4 << 20;
+        type Large = Option<[bool; LEN]>;
+
+        let mut buffer = vec![0u8; <Large as SerialSize>::SIZE];
+        buffer[0] = 1;
+        buffer[LEN] = 1;
+
+        thread::Builder::new()
+            .stack_size(256 << 10)
+            .spawn(move || {
+                let buffer: &[u8; <Large as SerialSize>::SIZE] =
+                    buffer.as_slice().try_into().unwrap();
+                let layout = Layout::new::<Large>();
+                unsafe {
+                    let into = alloc(layout).cast::<MaybeUninit<Large>>();
+                    assert!(!into.is_null());
+                    let value = Large::deserialize_into_uninit(&mut *into, buffer)
+                        .expect("deserialization failed")
+                        .as_ref()
+                        .expect("deserialized `None`");
+                    assert!(!value[0]);
+                    assert!(value[LEN - 1]);
+                    dealloc(into.cast(), layout);
+                }
+            })
+            .unwrap()
+            .join()
+            .expect("deserialization failed or overflowed the stack");
+    }
+
+    #[test]
+    fn test_stack_usage_tagged() {
+        extern crate std;
+
+        use core::mem::MaybeUninit;
+        use std::alloc::{alloc, dealloc, Layout};
+        use std::thread;
+        use std::vec;
+
+        // a tagged payload, which cannot be deserialized in place
+        const LEN: usize = 1 << 20;
+        type Large = Option<[u8; LEN]>;
+
+        // the payload goes through one temporary,
+        // which unoptimized builds move a few more times
+        let temporaries = if cfg!(debug_assertions) { 8 } else { 1 };
+        let stack = temporaries * LEN + (256 << 10);
+        let mut buffer = vec![0u8; <Large as SerialSize>::SIZE];
+        buffer[0] = 1;
+        buffer[LEN] = 7;
+
+        thread::Builder::new()
+            .stack_size(stack)
+            .spawn(move || {
+                let buffer: &[u8; <Large as SerialSize>::SIZE] =
+                    buffer.as_slice().try_into().unwrap();
+                let layout = Layout::new::<Large>();
+                unsafe {
+                    let into = alloc(layout).cast::<MaybeUninit<Large>>();
+                    assert!(!into.is_null());
+                    let value = Large::deserialize_into_uninit(&mut *into, buffer)
+                        .expect("deserialization failed")
+                        .as_ref()
+                        .expect("deserialized `None`");
+                    assert_eq!(7, value[LEN - 1]);
+                    dealloc(into.cast(), layout);
+                }
+            })
+            .unwrap()
+            .join()
+            .expect("deserialization failed or overflowed the stack");
+    }
 }
@@ -0,0 +1,642 @@
+use crate::impls::core::tuple::DeserializeTuple2Error;
+use crate::impls::IllegalBitPattern;
+use crate::schema::{sequence, Layout, Schema, Variant};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
+use core::ops::{
+    Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+use core::ptr::NonNull;
+use split_array::SplitArray;
+
+/// A range that rejects `start > end` during deserialization
+/// with [`DeserializeRangeError::Reversed`].
+///
+/// Serializes exactly like the wrapped range.
+#[repr(transparent)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Ordered<R>(pub R);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeserializeRangeError<T> {
+    Start(T),
+    End(T),
+    /// The start of the range is greater than its end.
+    /// Only returned by [`Ordered`] ranges.
+    Reversed,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeserializeBoundError<T> {
+    IllegalTag,
+    Data(T),
+}
+
+/// A range deserializes like the tuple `(start, end)`.
+impl<T> From<DeserializeTuple2Error<T, T>> for DeserializeRangeError<T> {
+    fn from(error: DeserializeTuple2Error<T, T>) -> Self {
+        match error {
+            | DeserializeTuple2Error::T0(error) => Self::Start(error),
+            | DeserializeTuple2Error::T1(error) => Self::End(error),
+        }
+    }
+}
+
+impl<T> From<IllegalBitPattern> for DeserializeBoundError<T> {
+    fn from(_error: IllegalBitPattern) -> Self {
+        Self::IllegalTag
+    }
+}
+
+impl<T: SerialSize> SerialSize for Range<T> {
+    const SIZE: usize = <(T, T) as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for Range<T> {
+    const SCHEMA: Layout = Layout::structure(
+        "Range",
+        &sequence(0, [("start", &T::SCHEMA), ("end", &T::SCHEMA)]),
+    );
+}
+
+impl<T: Serialize> Serialize for Range<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (start, buffer) = buffer.split_arr_mut();
+        let (end, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.start.serialize(start);
+        self.end.serialize(end);
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (start, buffer) = buffer.split_arr_mut();
+        let (end, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.start.try_serialize(start)?;
+        self.end.try_serialize(end)
+    }
+}
+
+impl<T: Deserialize> Deserialize for Range<T>
+where
+    [(); <Self as SerialSize>::SIZE - <(T, T) as SerialSize>::SIZE]:,
+    [(); <(T, T) as SerialSize>::SIZE
+        - <T as SerialSize>::SIZE
+        - <T as SerialSize>::SIZE]:,
+{
+    type Error = DeserializeRangeError<<T as Deserialize>::Error>;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        <(T, T)>::validate(head).map_err(Into::into)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        let (start, end) = <(T, T)>::deserialize(head)?;
+        unsafe {
+            // Safety:
+            // `into` is valid for writes
+            into.write(start..end);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: SerialSize> SerialSize for RangeInclusive<T> {
+    const SIZE: usize = <(T, T) as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for RangeInclusive<T> {
+    const SCHEMA: Layout = Layout::structure(
+        "RangeInclusive",
+        &sequence(0, [("start", &T::SCHEMA), ("end", &T::SCHEMA)]),
+    );
+}
+
+/// Only the bounds of a [`RangeInclusive`] are serialized.
+/// An exhausted range deserializes as a fresh range with the same bounds.
+impl<T: Serialize> Serialize for RangeInclusive<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (start, buffer) = buffer.split_arr_mut();
+        let (end, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.start().serialize(start);
+        self.end().serialize(end);
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (start, buffer) = buffer.split_arr_mut();
+        let (end, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.start().try_serialize(start)?;
+        self.end().try_serialize(end)
+    }
+}
+
+impl<T: Deserialize> Deserialize for RangeInclusive<T>
+where
+    [(); <Self as SerialSize>::SIZE - <(T, T) as SerialSize>::SIZE]:,
+    [(); <(T, T) as SerialSize>::SIZE
+        - <T as SerialSize>::SIZE
+        - <T as SerialSize>::SIZE]:,
+{
+    type Error = DeserializeRangeError<<T as Deserialize>::Error>;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        <(T, T)>::validate(head).map_err(Into::into)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        let (start, end) = <(T, T)>::deserialize(head)?;
+        unsafe {
+            // Safety:
+            // `into` is valid for writes
+            into.write(RangeInclusive::new(start, end));
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_for_half_open_range {
+    ($range:ident, $field:ident) => {
+        impl<T: SerialSize> SerialSize for $range<T> {
+            const SIZE: usize = <T as SerialSize>::SIZE;
+        }
+
+        impl<T: Schema> Schema for $range<T> {
+            const SCHEMA: Layout = Layout::structure(
+                stringify!($range),
+                &sequence(0, [(stringify!($field), &T::SCHEMA)]),
+            );
+        }
+
+        impl<T: Serialize> Serialize for $range<T>
+        where
+            [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+        {
+            fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                let (head, tail) = buffer.split_arr_mut();
+                debug_assert_eq!(0, tail.len());
+                self.$field.serialize(head);
+            }
+
+            fn try_serialize(
+                &self,
+                buffer: &mut [u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), SerializeError> {
+                let (head, tail) = buffer.split_arr_mut();
+                debug_assert_eq!(0, tail.len());
+                self.$field.try_serialize(head)
+            }
+        }
+
+        impl<T: Deserialize> Deserialize for $range<T>
+        where
+            [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+        {
+            type Error = <T as Deserialize>::Error;
+
+            fn validate(
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                T::validate(head)
+            }
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+
+                let into = into.as_ptr();
+                unsafe {
+                    // Safety:
+                    // - taking a raw ref of a place is always safe
+                    // - `into` is valid for writes, therefore,
+                    //   any derived pointer is also valid for writes
+                    let field = NonNull::new_unchecked(&raw mut (*into).$field);
+                    T::deserialize_raw(field, head)
+                }
+            }
+        }
+    };
+}
+
+impl_for_half_open_range!(RangeFrom, start);
+impl_for_half_open_range!(RangeTo, end);
+
+impl<T: SerialSize> SerialSize for RangeToInclusive<T> {
+    const SIZE: usize = <T as SerialSize>::SIZE;
+}
+
+impl<T: Schema> Schema for RangeToInclusive<T> {
+    const SCHEMA: Layout =
+        Layout::structure("RangeToInclusive", &sequence(0, [("end", &T::SCHEMA)]));
+}
+
+impl<T: Serialize> Serialize for RangeToInclusive<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.end.serialize(head);
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.end.try_serialize(head)
+    }
+}
+
+impl<T: Deserialize> Deserialize for RangeToInclusive<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = <T as Deserialize>::Error;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        T::validate(head)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        let end = T::deserialize(head)?;
+        unsafe {
+            // Safety:
+            // `into` is valid for writes
+            into.write(..=end);
+        }
+
+        Ok(())
+    }
+}
+
+impl SerialSize for RangeFull {
+    const SIZE: usize = 0;
+}
+
+impl Schema for RangeFull {
+    const SCHEMA: Layout = Layout::structure("RangeFull", &[]);
+}
+
+impl Serialize for RangeFull {
+    fn serialize(&self, _buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+}
+
+impl Deserialize for RangeFull {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        _buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(..);
+        Ok(())
+    }
+}
+
+impl<R: SerialSize> SerialSize for Ordered<R> {
+    const SIZE: usize = <R as SerialSize>::SIZE;
+}
+
+/// The layout of the range, which does not capture that its start must not exceed its end.
+impl<R: Schema> Schema for Ordered<R> {
+    const SCHEMA: Layout = R::SCHEMA;
+}
+
+impl<R: Serialize> Serialize for Ordered<R>
+where
+    [(); <Self as SerialSize>::SIZE - <R as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.0.serialize(head)
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.0.try_serialize(head)
+    }
+}
+
+macro_rules! impl_deserialize_for_ordered {
+    ($range:ident, $bound:ident => $start:expr, $end:expr) => {
+        impl<T: PartialOrd, E> Deserialize for Ordered<$range<T>>
+        where
+            $range<T>: Deserialize<Error = DeserializeRangeError<E>>,
+            [(); <Self as SerialSize>::SIZE - <$range<T> as SerialSize>::SIZE]:,
+        {
+            type Error = DeserializeRangeError<E>;
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+
+                // Safety:
+                // `Ordered` is `repr(transparent)`
+                let range = into.cast::<$range<T>>();
+                unsafe {
+                    <$range<T> as Deserialize>::deserialize_raw(range, head)?;
+
+                    let $bound = range.as_ref();
+                    if $start > $end {
+                        // Safety:
+                        // `range` was just initialized and is not used afterwards
+                        range.drop_in_place();
+                        return Err(DeserializeRangeError::Reversed);
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_deserialize_for_ordered!(Range, range => &range.start, &range.end);
+impl_deserialize_for_ordered!(RangeInclusive, range => range.start(), range.end());
+
+impl<T: SerialSize> SerialSize for Bound<T> {
+    const SIZE: usize = <u8 as SerialSize>::SIZE + <T as SerialSize>::SIZE;
+}
+
+const TAG_INCLUDED: u8 = 0;
+const TAG_EXCLUDED: u8 = 1;
+const TAG_UNBOUNDED: u8 = 2;
+
+impl<T: Schema> Schema for Bound<T> {
+    const SCHEMA: Layout = Layout::enumeration(
+        "Bound",
+        <Self as SerialSize>::SIZE,
+        &u8::SCHEMA,
+        &[
+            Variant {
+                name: "Included",
+                tag: TAG_INCLUDED as i64,
+                fields: &sequence(<u8 as SerialSize>::SIZE, [("0", &T::SCHEMA)]),
+            },
+            Variant {
+                name: "Excluded",
+                tag: TAG_EXCLUDED as i64,
+                fields: &sequence(<u8 as SerialSize>::SIZE, [("0", &T::SCHEMA)]),
+            },
+            Variant {
+                name: "Unbounded",
+                tag: TAG_UNBOUNDED as i64,
+                fields: &[],
+            },
+        ],
+        false,
+    );
+}
+
+impl<T: Serialize> Serialize for Bound<T>
+where
+    [(); <Self as SerialSize>::SIZE - <u8 as SerialSize>::SIZE]:,
+    [(); <Self as SerialSize>::SIZE - <u8 as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (tag, buffer) = buffer.split_arr_mut();
+        let (data, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        match self {
+            | Bound::Included(value) => {
+                *tag = [TAG_INCLUDED];
+                value.serialize(data);
+            }
+            | Bound::Excluded(value) => {
+                *tag = [TAG_EXCLUDED];
+                value.serialize(data);
+            }
+            | Bound::Unbounded => {
+                *tag = [TAG_UNBOUNDED];
+                data.fill(0);
+            }
+        }
+    }
+
+    fn try_serialize(
+        &self,
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), SerializeError> {
+        let (tag, buffer) = buffer.split_arr_mut();
+        let (data, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        match self {
+            | Bound::Included(value) => {
+                *tag = [TAG_INCLUDED];
+                value.try_serialize(data)
+            }
+            | Bound::Excluded(value) => {
+                *tag = [TAG_EXCLUDED];
+                value.try_serialize(data)
+            }
+            | Bound::Unbounded => {
+                *tag = [TAG_UNBOUNDED];
+                data.fill(0);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: Deserialize> Deserialize for Bound<T>
+where
+    [(); <Self as SerialSize>::SIZE - <u8 as SerialSize>::SIZE]:,
+    [(); <Self as SerialSize>::SIZE - <u8 as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = DeserializeBoundError<<T as Deserialize>::Error>;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (tag, buffer) = buffer.split_arr();
+        let (data, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        match *tag {
+            | [TAG_INCLUDED | TAG_EXCLUDED] => {
+                T::validate(data).map_err(DeserializeBoundError::Data)
+            }
+            | [TAG_UNBOUNDED] => Ok(()),
+            | _ => Err(IllegalBitPattern)?,
+        }
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (tag, buffer) = buffer.split_arr();
+        let (data, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+
+        let value = match *tag {
+            | [TAG_INCLUDED] => Bound::Included(
+                T::deserialize(data).map_err(DeserializeBoundError::Data)?,
+            ),
+            | [TAG_EXCLUDED] => Bound::Excluded(
+                T::deserialize(data).map_err(DeserializeBoundError::Data)?,
+            ),
+            | [TAG_UNBOUNDED] => Bound::Unbounded,
+            | _ => Err(IllegalBitPattern)?,
+        };
+        unsafe {
+            // Safety:
+            // `into` is valid for writes
+            into.write(value);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeserializeBoundError, DeserializeRangeError, Ordered};
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+    use core::ops::Bound;
+
+    #[test]
+    fn test_ranges() {
+        assert_serial_eq!(core::ops::Range<u32>, &(3..17));
+        assert_serial_eq!(core::ops::Range<i64>, &(-40..-4));
+        assert_serial_eq!(core::ops::RangeInclusive<u16>, &(3..=17));
+        assert_serial_eq!(core::ops::RangeFrom<f32>, &(1.5..));
+        assert_serial_eq!(core::ops::RangeTo<char>, &(..'z'));
+        assert_serial_eq!(core::ops::RangeToInclusive<i8>, &(..=-1));
+        assert_serial_eq!(core::ops::RangeFull, &(..));
+        assert_serial_eq!(core::ops::Range<Option<bool>>, &(Some(false)..None));
+    }
+
+    #[test]
+    fn test_ordered() {
+        assert_serial_eq!(Ordered<core::ops::Range<u32>>, &Ordered(3..17));
+        assert_serial_eq!(Ordered<core::ops::Range<u32>>, &Ordered(3..3));
+        assert_serial_eq!(Ordered<core::ops::RangeInclusive<i8>>, &Ordered(-3..=-3));
+
+        let reversed = core::ops::Range {
+            start: 17u32,
+            end: 3,
+        };
+        let mut buf = [0; <core::ops::Range<u32> as SerialSize>::SIZE];
+        reversed.serialize(&mut buf);
+        assert_eq!(Ok(reversed), core::ops::Range::<u32>::deserialize(&buf));
+        assert_eq!(
+            Err(DeserializeRangeError::Reversed),
+            Ordered::<core::ops::Range<u32>>::deserialize(&buf)
+        );
+
+        let mut buf = [0; <core::ops::RangeInclusive<f64> as SerialSize>::SIZE];
+        (1.0..=f64::NAN).serialize(&mut buf);
+        Ordered::<core::ops::RangeInclusive<f64>>::deserialize(&buf)
+            .expect("NaN bounds are not ordered");
+        (1.0..=-1.0).serialize(&mut buf);
+        assert_eq!(
+            Err(DeserializeRangeError::Reversed),
+            Ordered::<core::ops::RangeInclusive<f64>>::deserialize(&buf)
+        );
+    }
+
+    #[test]
+    fn test_range_error() {
+        let mut buf = [0; <core::ops::Range<bool> as SerialSize>::SIZE];
+        buf[1] = 2;
+        assert_eq!(
+            Err(DeserializeRangeError::End(crate::impls::IllegalBitPattern)),
+            core::ops::Range::<bool>::deserialize(&buf)
+        );
+
+        buf[0] = 3;
+        assert_eq!(
+            Err(DeserializeRangeError::Start(
+                crate::impls::IllegalBitPattern
+            )),
+            core::ops::RangeInclusive::<bool>::validate(&buf)
+        );
+    }
+
+    #[test]
+    fn test_bound() {
+        assert_serial_eq!(Bound<u64>, &Bound::Included(12));
+        assert_serial_eq!(Bound<u64>, &Bound::Excluded(u64::MAX));
+        assert_serial_eq!(Bound<u64>, &Bound::Unbounded);
+        assert_serial_eq!(Bound<Bound<bool>>, &Bound::Excluded(Bound::Unbounded));
+
+        let mut buf = [0; <Bound<u64> as SerialSize>::SIZE];
+        buf[0] = 3;
+        assert_eq!(
+            Err(DeserializeBoundError::IllegalTag),
+            Bound::<u64>::deserialize(&buf)
+        );
+        assert_eq!(
+            Err(DeserializeBoundError::IllegalTag),
+            Bound::<u64>::validate(&buf)
+        );
+
+        let mut buf = [0; <Bound<bool> as SerialSize>::SIZE];
+        buf[1] = 2;
+        assert_eq!(
+            Err(DeserializeBoundError::Data(crate::impls::IllegalBitPattern)),
+            Bound::<bool>::validate(&buf)
+        );
+        buf[0] = 2;
+        assert_eq!(Ok(()), Bound::<bool>::validate(&buf));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::impls::IllegalBitPattern;
+use crate::schema::{sequence, Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize};
+use core::ptr::NonNull;
+use core::time::Duration;
+use split_array::SplitArray;
+
+pub(crate) const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+impl SerialSize for Duration {
+    const SIZE: usize = <u64 as SerialSize>::SIZE + <u32 as SerialSize>::SIZE;
+}
+
+/// The layout does not capture that `nanos` must be below one second.
+impl Schema for Duration {
+    const SCHEMA: Layout = Layout::structure(
+        "Duration",
+        &sequence(0, [("secs", &u64::SCHEMA), ("nanos", &u32::SCHEMA)]),
+    );
+}
+
+impl Serialize for Duration {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (secs, nanos) = buffer.split_arr_mut();
+        self.as_secs().serialize(secs);
+        self.subsec_nanos().serialize(nanos);
+    }
+}
+
+impl Deserialize for Duration {
+    type Error = IllegalBitPattern;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (_, nanos): (&[u8; <u64 as SerialSize>::SIZE], _) = buffer.split_arr();
+        match u32::deserialize(nanos).unwrap() {
+            | nanos if nanos < NANOS_PER_SEC => Ok(()),
+            | _ => Err(IllegalBitPattern),
+        }
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        Self::validate(buffer)?;
+        let (secs, nanos) = buffer.split_arr();
+        let secs = u64::deserialize(secs).unwrap();
+        let nanos = u32::deserialize(nanos).unwrap();
+
+        into.write(Duration::new(secs, nanos));
+        Ok(())
+    }
+}
+
+/// A [`Duration`] serialized as a whole number of units of `NANOS_PER_UNIT` nanoseconds,
+/// stored in the integer type `R`.
+///
+/// Converting a [`Duration`] into a `DurationUnits` truncates towards zero
+/// and fails with [`DurationOverflow`] if the result does not fit into `R`.
+/// Any value of `R` is a valid serialized form.
+///
+/// `NANOS_PER_UNIT` must be a non-zero divisor of one second.
+/// Use one of the aliases [`Secs`], [`Millis`], [`Micros`] or [`Nanos`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct DurationUnits<R, const NANOS_PER_UNIT: u64>(R);
+
+/// A [`Duration`] serialized as whole seconds.
+pub type Secs<R> = DurationUnits<R, 1_000_000_000>;
+/// A [`Duration`] serialized as whole milliseconds.
+pub type Millis<R> = DurationUnits<R, 1_000_000>;
+/// A [`Duration`] serialized as whole microseconds.
+pub type Micros<R> = DurationUnits<R, 1_000>;
+/// A [`Duration`] serialized as whole nanoseconds.
+pub type Nanos<R> = DurationUnits<R, 1>;
+
+/// The error returned when a [`Duration`] does not fit into a [`DurationUnits`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub struct DurationOverflow;
+
+macro_rules! impl_duration_units {
+    ($repr:ty) => {
+        impl<const NANOS_PER_UNIT: u64> DurationUnits<$repr, NANOS_PER_UNIT> {
+            const VALID_UNIT: () = assert!(
+                NANOS_PER_UNIT != 0 && NANOS_PER_SEC as u64 % NANOS_PER_UNIT == 0,
+                "a duration unit must be a non-zero divisor of one second"
+            );
+
+            /// Create a `DurationUnits` from a raw number of units.
+            pub const fn from_units(units: $repr) -> Self {
+                let () = Self::VALID_UNIT;
+                Self(units)
+            }
+
+            /// The raw number of units.
+            pub const fn units(self) -> $repr {
+                self.0
+            }
+
+            /// The represented [`Duration`].
+            pub const fn get(self) -> Duration {
+                let () = Self::VALID_UNIT;
+                let units_per_sec = NANOS_PER_SEC as u64 / NANOS_PER_UNIT;
+                let units = self.0 as u64;
+                Duration::new(
+                    units / units_per_sec,
+                    ((units % units_per_sec) * NANOS_PER_UNIT) as u32,
+                )
+            }
+        }
+
+        impl<const NANOS_PER_UNIT: u64> TryFrom<Duration>
+            for DurationUnits<$repr, NANOS_PER_UNIT>
+        {
+            type Error = DurationOverflow;
+
+            fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+                let () = Self::VALID_UNIT;
+                let units = duration.as_nanos() / NANOS_PER_UNIT as u128;
+                Ok(Self(
+                    <$repr>::try_from(units).map_err(|_| DurationOverflow)?,
+                ))
+            }
+        }
+
+        impl<const NANOS_PER_UNIT: u64> From<DurationUnits<$repr, NANOS_PER_UNIT>>
+            for Duration
+        {
+            fn from(units: DurationUnits<$repr, NANOS_PER_UNIT>) -> Self {
+                units.get()
+            }
+        }
+
+        impl<const NANOS_PER_UNIT: u64> SerialSize
+            for DurationUnits<$repr, NANOS_PER_UNIT>
+        {
+            const SIZE: usize = <$repr as SerialSize>::SIZE;
+        }
+
+        impl<const NANOS_PER_UNIT: u64> Schema for DurationUnits<$repr, NANOS_PER_UNIT> {
+            const SCHEMA: Layout = <$repr>::SCHEMA;
+        }
+
+        impl<const NANOS_PER_UNIT: u64> Serialize
+            for DurationUnits<$repr, NANOS_PER_UNIT>
+        {
+            fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                self.0.serialize(buffer)
+            }
+        }
+
+        impl<const NANOS_PER_UNIT: u64> Deserialize
+            for DurationUnits<$repr, NANOS_PER_UNIT>
+        {
+            type Error = core::convert::Infallible;
+
+            fn validate(
+                _buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                into.write(Self::from_units(<$repr>::deserialize(buffer)?));
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_duration_units!(u8);
+impl_duration_units!(u16);
+impl_duration_units!(u32);
+impl_duration_units!(u64);
+
+#[cfg(test)]
+mod tests {
+    use super::{DurationOverflow, Micros, Millis, Nanos, Secs};
+    use crate::impls::IllegalBitPattern;
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+    use core::time::Duration;
+    use split_array::SplitArray;
+
+    #[test]
+    fn test_duration() {
+        assert_serial_eq!(Duration, &Duration::ZERO);
+        assert_serial_eq!(Duration, &Duration::MAX);
+        assert_serial_eq!(Duration, &Duration::new(1234, 567_890));
+        assert_serial_eq!(Duration, &Duration::from_nanos(999_999_999));
+    }
+
+    #[test]
+    fn test_duration_illegal_nanos() {
+        let mut buf = [0; <Duration as SerialSize>::SIZE];
+        Duration::new(3, 0).serialize(&mut buf);
+        let (_, nanos): (&mut [u8; <u64 as SerialSize>::SIZE], _) = buf.split_arr_mut();
+        1_000_000_000u32.serialize(nanos);
+
+        assert_eq!(Err(IllegalBitPattern), Duration::deserialize(&buf));
+        assert_eq!(Err(IllegalBitPattern), Duration::validate(&buf));
+    }
+
+    #[test]
+    fn test_units() {
+        let load = |units: &Millis<u32>| units.units();
+        assert_serial_eq!(Millis<u32>, &Millis::<u32>::from_units(0), load);
+        assert_serial_eq!(Millis<u32>, &Millis::<u32>::from_units(u32::MAX), load);
+
+        assert_eq!(
+            Ok(Millis::<u32>::from_units(1_500)),
+            Millis::try_from(Duration::new(1, 500_999_999))
+        );
+        assert_eq!(
+            Duration::new(1, 500_000_000),
+            Millis::<u32>::from_units(1_500).get()
+        );
+        assert_eq!(Duration::new(70, 0), Secs::<u8>::from_units(70).get());
+        assert_eq!(
+            Duration::new(u64::MAX / 1_000_000, 551_615_000),
+            Micros::<u64>::from_units(u64::MAX).get()
+        );
+        assert_eq!(
+            Duration::from_nanos(u64::MAX),
+            Nanos::<u64>::from_units(u64::MAX).get()
+        );
+    }
+
+    #[test]
+    fn test_units_overflow() {
+        assert_eq!(
+            Err(DurationOverflow),
+            Millis::<u32>::try_from(Duration::from_millis(u32::MAX as u64 + 1))
+        );
+        assert_eq!(
+            Err(DurationOverflow),
+            Secs::<u8>::try_from(Duration::from_secs(256))
+        );
+        assert_eq!(Err(DurationOverflow), Nanos::<u64>::try_from(Duration::MAX));
+        assert!(Secs::<u64>::try_from(Duration::MAX).is_ok());
+    }
+}
@@ -1,8 +1,10 @@
-use crate::{Deserialize, SerialSize, Serialize};
+use crate::schema::{sequence, Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
 use arrise_macro::{
     deserialize_error_assoc_type_for_tuple, deserialize_error_type_for_tuple,
     impl_for_tuples,
 };
+use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 
 macro_rules! impl_serial_size_for_tuple {
@@ -26,6 +28,37 @@ macro_rules! impl_serialize_for_tuple {
                 <$ts as Serialize>::serialize(&self.$fields, head);
                 )*
             }
+
+            #[allow(unused_variables, non_snake_case)]
+            fn try_serialize(
+                &self,
+                buffer: &mut [u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), SerializeError> {
+                $(
+                let (head, buffer) = split_array::split_arr_mut(buffer);
+                <$ts as Serialize>::try_serialize(&self.$fields, head)?;
+                )*
+
+                Ok(())
+            }
+
+            #[allow(unused_variables, non_snake_case)]
+            fn serialize_uninit<'a>(
+                &self,
+                buffer: &'a mut MaybeUninit<[u8; <Self as SerialSize>::SIZE]>,
+            ) -> &'a mut [u8; <Self as SerialSize>::SIZE] {
+                {
+                    let buffer = &mut *buffer;
+                    $(
+                    let (head, buffer) = crate::impls::split_uninit_mut(buffer);
+                    crate::impls::serialize_uninit_into::<$ts>(&self.$fields, head);
+                    )*
+                }
+
+                // Safety:
+                // the fields just initialized the whole buffer
+                unsafe { buffer.assume_init_mut() }
+            }
         }
     };
 }
@@ -40,6 +73,41 @@ macro_rules! impl_deserialize_for_tuple {
 
             type Error = deserialize_error_assoc_type_for_tuple!(($($ts,)*));
 
+            #[allow(unused_variables, non_snake_case)]
+            fn validate(buffer: &[u8; Self::SIZE]) -> Result<(), Self::Error> {
+                $(
+                let (head, buffer) = split_array::split_arr(buffer);
+                <$ts as Deserialize>::validate(head)
+                    .map_err(|e| <Self as Deserialize>::Error::$ts(e))?;
+                )*
+
+                Ok(())
+            }
+
+            #[allow(unused_variables, non_snake_case)]
+            fn deserialize_in_place(
+                &mut self,
+                buffer: &[u8; Self::SIZE]
+            ) -> Result<(), Self::Error> {
+                // validate all fields first, so that `self` is left untouched on error
+                Self::validate(buffer)?;
+                self.deserialize_in_place_unchecked(buffer)
+            }
+
+            #[allow(unused_variables, non_snake_case)]
+            fn deserialize_in_place_unchecked(
+                &mut self,
+                buffer: &[u8; Self::SIZE]
+            ) -> Result<(), Self::Error> {
+                $(
+                let (head, buffer) = split_array::split_arr(buffer);
+                <$ts as Deserialize>::deserialize_in_place_unchecked(&mut self.$fields, head)
+                    .map_err(|e| <Self as Deserialize>::Error::$ts(e))?;
+                )*
+
+                Ok(())
+            }
+
             #[allow(unused_variables, non_snake_case)]
             unsafe fn deserialize_raw(
                 into: NonNull<Self>,
@@ -66,14 +134,62 @@ macro_rules! impl_deserialize_for_tuple {
     };
 }
 
+/// A tuple is a struct with fields named `0`, `1`, …
+macro_rules! impl_schema_for_tuple {
+    (($($fields:tt: $ts:ident,)*)) => {
+        impl<$($ts: Schema,)*> Schema for ($($ts,)*) {
+            const SCHEMA: Layout = Layout::structure(
+                "tuple",
+                &sequence(0, [$((stringify!($fields), &<$ts as Schema>::SCHEMA),)*]),
+            );
+        }
+    };
+}
+
 macro_rules! impl_for_tuple {
     (($($fields:tt: $ts:ident,)*)) => {
         impl_serial_size_for_tuple!(($($ts,)*));
         impl_serialize_for_tuple!(($($fields: $ts,)*));
         impl_deserialize_for_tuple!(($($fields: $ts,)*));
+        impl_schema_for_tuple!(($($fields: $ts,)*));
     };
 }
 
+impl SerialSize for () {
+    const SIZE: usize = 0;
+}
+
+impl Schema for () {
+    const SCHEMA: Layout = Layout::structure("()", &[]);
+}
+
+impl Serialize for () {
+    fn serialize(&self, _buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+
+    fn serialize_uninit<'a>(
+        &self,
+        buffer: &'a mut MaybeUninit<[u8; <Self as SerialSize>::SIZE]>,
+    ) -> &'a mut [u8; <Self as SerialSize>::SIZE] {
+        buffer.write([])
+    }
+}
+
+impl Deserialize for () {
+    type Error = core::convert::Infallible;
+
+    fn validate(_buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        _buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(());
+        Ok(())
+    }
+}
+
 #[cfg(not(feature = "large_tuples"))]
 impl_for_tuples!(12);
 #[cfg(feature = "large_tuples")]
@@ -81,7 +197,7 @@ impl_for_tuples!(64);
 
 #[cfg(test)]
 mod tests {
-    use crate::assert_serial_eq;
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
 
     #[test]
     fn test_complex() {
@@ -106,4 +222,57 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_to_bytes() {
+        type T = (u16, Option<[u8; 3]>, [(bool, char); 2], ());
+        let value: T = (0x1234, None, [(true, 'x'), (false, 'ÿ')], ());
+
+        let mut buf = [0xff; <T as SerialSize>::SIZE];
+        value.serialize(&mut buf);
+        assert_eq!(buf, value.to_bytes());
+        assert_eq!(Ok(value), T::deserialize(&value.to_bytes()));
+    }
+
+    #[test]
+    fn test_validate() {
+        type T = (u32, Option<bool>, [char; 2]);
+
+        let mut buf = [0; <T as SerialSize>::SIZE];
+        (7u32, Some(true), ['a', 'b']).serialize(&mut buf);
+        assert!(T::validate(&buf).is_ok());
+
+        let mut invalid = buf;
+        invalid[5] = 2;
+        assert!(T::validate(&invalid).is_err());
+
+        let mut invalid = buf;
+        // a surrogate in the last char
+        0xd800u32.serialize(
+            (&mut invalid[<T as SerialSize>::SIZE - 4..])
+                .try_into()
+                .unwrap(),
+        );
+        assert!(T::validate(&invalid).is_err());
+        assert_eq!(T::deserialize(&invalid).map(drop), T::validate(&invalid));
+    }
+
+    #[test]
+    fn test_in_place() {
+        type T = (u8, [Option<u32>; 2], bool);
+
+        let mut value: T = (1, [Some(2), None], true);
+        let mut buf = [0; <T as SerialSize>::SIZE];
+        (9u8, [None, Some(7u32)], false).serialize(&mut buf);
+        value
+            .deserialize_in_place(&buf)
+            .expect("deserialization failed");
+        assert_eq!((9, [None, Some(7)], false), value);
+
+        // an illegal bool in the last field
+        let mut invalid = buf;
+        invalid[<T as SerialSize>::SIZE - 1] = 2;
+        assert!(value.deserialize_in_place(&invalid).is_err());
+        assert_eq!((9, [None, Some(7)], false), value);
+    }
 }
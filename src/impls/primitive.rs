@@ -1,7 +1,12 @@
 use crate::impls::IllegalBitPattern;
+use crate::schema::{Layout, Primitive, Schema};
 use crate::{Deserialize, SerialSize, Serialize};
 use core::char::CharTryFromError;
-use core::mem::size_of;
+use core::mem::{size_of, MaybeUninit};
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize,
+    NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
 use core::ptr::NonNull;
 
 macro_rules! to_e_bytes {
@@ -29,26 +34,120 @@ macro_rules! from_e_bytes {
 }
 
 macro_rules! impl_primitive {
-    ($ty:ty) => {
+    ($ty:ident, $bits:ident, $primitive:ident) => {
         impl crate::SerialSize for $ty {
             const SIZE: usize = core::mem::size_of::<$ty>();
         }
 
+        impl Schema for $ty {
+            const SCHEMA: Layout = Layout::primitive(
+                stringify!($ty),
+                size_of::<$ty>(),
+                Primitive::$primitive,
+            );
+        }
+
         impl crate::Serialize for $ty {
             fn serialize(&self, buffer: &mut [u8; <Self as crate::SerialSize>::SIZE]) {
                 *buffer = to_e_bytes!(self);
             }
+
+            #[inline(always)]
+            fn serialize_uninit<'a>(
+                &self,
+                buffer: &'a mut MaybeUninit<[u8; <Self as crate::SerialSize>::SIZE]>,
+            ) -> &'a mut [u8; <Self as crate::SerialSize>::SIZE] {
+                buffer.write(to_e_bytes!(self))
+            }
+
+            crate::plain::cfg_native_endian! {
+                #[inline(always)]
+                fn serialize_slice(values: &[Self], buffer: &mut [u8]) {
+                    <Self as crate::plain::PlainLayout>::serialize_plain_slice(values, buffer)
+                }
+            }
+
+            crate::plain::cfg_foreign_endian! {
+                /// Swaps the bytes of each value as an unsigned integer of the same size,
+                /// which, unlike converting e.g. a float, vectorizes into byte shuffles.
+                #[inline(always)]
+                fn serialize_slice(values: &[Self], buffer: &mut [u8]) {
+                    debug_assert_eq!(values.len() * size_of::<$ty>(), buffer.len());
+                    // Safety:
+                    // `$bits` has the size and alignment of `$ty`,
+                    // and any bit pattern is a valid `$bits`
+                    let values = unsafe {
+                        core::slice::from_raw_parts(values.as_ptr().cast::<$bits>(), values.len())
+                    };
+                    for (value, buffer) in values.iter().zip(buffer.array_chunks_mut()) {
+                        *buffer = value.swap_bytes().to_ne_bytes();
+                    }
+                }
+            }
+
+            #[inline(always)]
+            fn try_serialize_slice(
+                values: &[Self],
+                buffer: &mut [u8],
+            ) -> Result<(), crate::SerializeError> {
+                Self::serialize_slice(values, buffer);
+                Ok(())
+            }
         }
 
         impl crate::Deserialize for $ty {
             type Error = core::convert::Infallible;
 
+            #[inline(always)]
+            fn validate(
+                _buffer: &[u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), <Self as crate::Deserialize>::Error> {
+                Ok(())
+            }
+
             unsafe fn deserialize_raw(
                 into: NonNull<Self>,
                 buffer: &[u8; <Self as crate::SerialSize>::SIZE],
             ) -> Result<(), <Self as crate::Deserialize>::Error> {
                 Ok(into.write(from_e_bytes!($ty, *buffer)))
             }
+
+            crate::plain::cfg_native_endian! {
+                #[inline(always)]
+                unsafe fn deserialize_raw_slice(
+                    into: NonNull<Self>,
+                    len: usize,
+                    buffer: &[u8],
+                ) -> Result<(), <Self as crate::Deserialize>::Error> {
+                    <Self as crate::plain::PlainLayout>::deserialize_plain_slice(
+                        into, len, buffer,
+                    );
+                    Ok(())
+                }
+            }
+
+            crate::plain::cfg_foreign_endian! {
+                /// Swaps the bytes of each value as an unsigned integer, like `serialize_slice`.
+                #[inline(always)]
+                unsafe fn deserialize_raw_slice(
+                    into: NonNull<Self>,
+                    len: usize,
+                    buffer: &[u8],
+                ) -> Result<(), <Self as crate::Deserialize>::Error> {
+                    debug_assert_eq!(len * size_of::<$ty>(), buffer.len());
+                    let into = into.as_ptr().cast::<$bits>();
+                    for (i, buffer) in buffer.array_chunks().enumerate() {
+                        let value = <$bits>::from_ne_bytes(*buffer).swap_bytes();
+                        // Safety:
+                        // - `into` is valid for writes of `len` values
+                        //   and `buffer` holds exactly `len` chunks
+                        // - `$bits` has the size and alignment of `$ty`,
+                        //   and any bit pattern is a valid `$ty`
+                        unsafe { into.add(i).write(value) };
+                    }
+                    Ok(())
+                }
+            }
         }
     };
 }
@@ -65,12 +164,33 @@ macro_rules! impl_atomic {
             const SIZE: usize = size_of::<$primitive>();
         }
 
+        #[$feature_gate]
+        #[cfg(target_has_atomic_load_store = $size)]
+        impl Schema for $ty {
+            const SCHEMA: Layout = <$primitive as Schema>::SCHEMA;
+        }
+
         #[$feature_gate]
         #[cfg(target_has_atomic_load_store = $size)]
         impl crate::Serialize for $ty {
             fn serialize(&self, buffer: &mut [u8; <Self as crate::SerialSize>::SIZE]) {
-                self.load(core::sync::atomic::Ordering::SeqCst)
-                    .serialize(buffer)
+                <Self as crate::impls::core::atomic::SerializeAtomic>::serialize_with_ordering(
+                    self,
+                    buffer,
+                    core::sync::atomic::Ordering::SeqCst,
+                )
+            }
+        }
+
+        #[$feature_gate]
+        #[cfg(target_has_atomic_load_store = $size)]
+        impl crate::impls::core::atomic::SerializeAtomic for $ty {
+            fn serialize_with_ordering(
+                &self,
+                buffer: &mut [u8; <Self as crate::SerialSize>::SIZE],
+                ordering: core::sync::atomic::Ordering,
+            ) {
+                self.load(ordering).serialize(buffer)
             }
         }
 
@@ -79,6 +199,12 @@ macro_rules! impl_atomic {
         impl crate::Deserialize for $ty {
             type Error = <$primitive as Deserialize>::Error;
 
+            fn validate(
+                buffer: &[u8; <Self as crate::SerialSize>::SIZE],
+            ) -> Result<(), <Self as crate::Deserialize>::Error> {
+                <$primitive>::validate(buffer)
+            }
+
             unsafe fn deserialize_raw(
                 into: NonNull<Self>,
                 buffer: &[u8; <Self as crate::SerialSize>::SIZE],
@@ -89,47 +215,71 @@ macro_rules! impl_atomic {
     };
 }
 macro_rules! impl_nonzero {
-    ($nonzero:ty, $primitive:ty) => {
+    ($nonzero:ident, $primitive:ty, $kind:ident) => {
         impl SerialSize for $nonzero {
             const SIZE: usize = size_of::<$primitive>();
         }
 
+        impl Schema for $nonzero {
+            const SCHEMA: Layout = Layout::primitive(
+                stringify!($nonzero),
+                size_of::<$primitive>(),
+                Primitive::$kind,
+            );
+        }
+
         impl Serialize for $nonzero {
             fn serialize(&self, buffer: &mut [u8; Self::SIZE]) {
                 self.get().serialize(buffer)
             }
+
+            fn serialize_uninit<'a>(
+                &self,
+                buffer: &'a mut MaybeUninit<[u8; Self::SIZE]>,
+            ) -> &'a mut [u8; Self::SIZE] {
+                self.get().serialize_uninit(buffer)
+            }
         }
 
         impl Deserialize for $nonzero {
             type Error = IllegalBitPattern;
 
+            fn validate(buffer: &[u8; Self::SIZE]) -> Result<(), Self::Error> {
+                match buffer.iter().all(|&byte| byte == 0) {
+                    | true => Err(IllegalBitPattern),
+                    | false => Ok(()),
+                }
+            }
+
             unsafe fn deserialize_raw(
                 into: NonNull<Self>,
                 buffer: &[u8; Self::SIZE],
             ) -> Result<(), Self::Error> {
-                Ok(into.write(
-                    Self::new(<$primitive>::deserialize(buffer).unwrap())
-                        .ok_or(IllegalBitPattern)?,
-                ))
+                Self::validate(buffer)?;
+                // Safety:
+                // `validate` just checked that the value is non-zero
+                Ok(into.write(Self::new_unchecked(
+                    <$primitive>::deserialize(buffer).unwrap(),
+                )))
             }
         }
     };
 }
 
-impl_primitive!(u8);
-impl_primitive!(u16);
-impl_primitive!(u32);
-impl_primitive!(u64);
-impl_primitive!(u128);
-impl_primitive!(usize);
-impl_primitive!(i8);
-impl_primitive!(i16);
-impl_primitive!(i32);
-impl_primitive!(i64);
-impl_primitive!(i128);
-impl_primitive!(isize);
-impl_primitive!(f32);
-impl_primitive!(f64);
+impl_primitive!(u8, u8, Unsigned);
+impl_primitive!(u16, u16, Unsigned);
+impl_primitive!(u32, u32, Unsigned);
+impl_primitive!(u64, u64, Unsigned);
+impl_primitive!(u128, u128, Unsigned);
+impl_primitive!(usize, usize, Unsigned);
+impl_primitive!(i8, u8, Signed);
+impl_primitive!(i16, u16, Signed);
+impl_primitive!(i32, u32, Signed);
+impl_primitive!(i64, u64, Signed);
+impl_primitive!(i128, u128, Signed);
+impl_primitive!(isize, usize, Signed);
+impl_primitive!(f32, u32, Float);
+impl_primitive!(f64, u64, Float);
 
 impl_atomic!(core::sync::atomic::AtomicBool, bool, "8");
 impl_atomic!(core::sync::atomic::AtomicU8, u8, "8");
@@ -155,41 +305,57 @@ impl_atomic!(
 );
 impl_atomic!(core::sync::atomic::AtomicIsize, isize, "ptr");
 
-impl_nonzero!(core::num::NonZeroI8, i8);
-impl_nonzero!(core::num::NonZeroI16, i16);
-impl_nonzero!(core::num::NonZeroI32, i32);
-impl_nonzero!(core::num::NonZeroI64, i64);
-impl_nonzero!(core::num::NonZeroI128, i128);
-impl_nonzero!(core::num::NonZeroIsize, isize);
-impl_nonzero!(core::num::NonZeroU8, u8);
-impl_nonzero!(core::num::NonZeroU16, u16);
-impl_nonzero!(core::num::NonZeroU32, u32);
-impl_nonzero!(core::num::NonZeroU64, u64);
-impl_nonzero!(core::num::NonZeroU128, u128);
-impl_nonzero!(core::num::NonZeroUsize, usize);
+impl_nonzero!(NonZeroI8, i8, NonZeroSigned);
+impl_nonzero!(NonZeroI16, i16, NonZeroSigned);
+impl_nonzero!(NonZeroI32, i32, NonZeroSigned);
+impl_nonzero!(NonZeroI64, i64, NonZeroSigned);
+impl_nonzero!(NonZeroI128, i128, NonZeroSigned);
+impl_nonzero!(NonZeroIsize, isize, NonZeroSigned);
+impl_nonzero!(NonZeroU8, u8, NonZeroUnsigned);
+impl_nonzero!(NonZeroU16, u16, NonZeroUnsigned);
+impl_nonzero!(NonZeroU32, u32, NonZeroUnsigned);
+impl_nonzero!(NonZeroU64, u64, NonZeroUnsigned);
+impl_nonzero!(NonZeroU128, u128, NonZeroUnsigned);
+impl_nonzero!(NonZeroUsize, usize, NonZeroUnsigned);
 
 impl SerialSize for bool {
     const SIZE: usize = <u8 as SerialSize>::SIZE;
 }
 
+impl Schema for bool {
+    const SCHEMA: Layout = Layout::primitive("bool", 1, Primitive::Bool);
+}
+
 impl Serialize for bool {
     fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
         *buffer = [*self as u8]
     }
+
+    fn serialize_uninit<'a>(
+        &self,
+        buffer: &'a mut MaybeUninit<[u8; <Self as SerialSize>::SIZE]>,
+    ) -> &'a mut [u8; <Self as SerialSize>::SIZE] {
+        buffer.write([*self as u8])
+    }
 }
 
 impl Deserialize for bool {
     type Error = IllegalBitPattern;
 
+    #[inline(always)]
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        match *buffer {
+            | [0 | 1] => Ok(()),
+            | _ => Err(IllegalBitPattern),
+        }
+    }
+
     unsafe fn deserialize_raw(
         into: NonNull<Self>,
         buffer: &[u8; <Self as SerialSize>::SIZE],
     ) -> Result<(), Self::Error> {
-        into.write(match *buffer {
-            | [0] => false,
-            | [1] => true,
-            | _ => Err(IllegalBitPattern)?,
-        });
+        Self::validate(buffer)?;
+        into.write(buffer[0] != 0);
         Ok(())
     }
 }
@@ -198,15 +364,30 @@ impl SerialSize for char {
     const SIZE: usize = size_of::<u32>();
 }
 
+impl Schema for char {
+    const SCHEMA: Layout = Layout::primitive("char", size_of::<u32>(), Primitive::Char);
+}
+
 impl Serialize for char {
     fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
         u32::serialize(&u32::from(*self), buffer)
     }
+
+    fn serialize_uninit<'a>(
+        &self,
+        buffer: &'a mut MaybeUninit<[u8; <Self as SerialSize>::SIZE]>,
+    ) -> &'a mut [u8; <Self as SerialSize>::SIZE] {
+        u32::from(*self).serialize_uninit(buffer)
+    }
 }
 
 impl Deserialize for char {
     type Error = CharTryFromError;
 
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        char::try_from(u32::deserialize(buffer).unwrap()).map(drop)
+    }
+
     unsafe fn deserialize_raw(
         into: NonNull<Self>,
         buffer: &[u8; <Self as SerialSize>::SIZE],
@@ -873,4 +1054,33 @@ mod tests {
             );
         }
     }
+
+    mod validate {
+        use crate::impls::IllegalBitPattern;
+        use crate::{Deserialize, SerialSize, Serialize};
+        use core::num::{NonZeroI16, NonZeroU32};
+
+        #[test]
+        fn test_bool() {
+            assert_eq!(Ok(()), bool::validate(&[0]));
+            assert_eq!(Ok(()), bool::validate(&[1]));
+            assert_eq!(Err(IllegalBitPattern), bool::validate(&[2]));
+        }
+
+        #[test]
+        fn test_char() {
+            let mut buf = [0; <char as SerialSize>::SIZE];
+            0xd800u32.serialize(&mut buf);
+            assert!(char::validate(&buf).is_err());
+            0x61u32.serialize(&mut buf);
+            assert_eq!(Ok(()), char::validate(&buf));
+        }
+
+        #[test]
+        fn test_nonzero() {
+            assert_eq!(Err(IllegalBitPattern), NonZeroU32::validate(&[0; 4]));
+            assert_eq!(Ok(()), NonZeroU32::validate(&[0, 0, 1, 0]));
+            assert_eq!(Ok(()), NonZeroI16::validate(&[0xff, 0xff]));
+        }
+    }
 }
@@ -0,0 +1,182 @@
+use crate::schema::{Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize, SerializeError};
+use core::ptr::NonNull;
+use split_array::SplitArray;
+use std::sync::{Mutex, PoisonError, RwLock};
+
+macro_rules! impl_for_lock {
+    ($lock:ident, $guard:ident) => {
+        impl<T: SerialSize> SerialSize for $lock<T> {
+            const SIZE: usize = <T as SerialSize>::SIZE;
+        }
+
+        impl<T: Schema> Schema for $lock<T> {
+            const SCHEMA: Layout = T::SCHEMA;
+        }
+
+        /// Blocks until the lock can be acquired.
+        /// [`serialize`](Serialize::serialize) ignores poisoning,
+        /// while [`try_serialize`](Serialize::try_serialize)
+        /// returns [`SerializeError::Poisoned`] for a poisoned lock.
+        impl<T: Serialize> Serialize for $lock<T>
+        where
+            [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+        {
+            fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                let (head, tail) = buffer.split_arr_mut();
+                debug_assert_eq!(0, tail.len());
+                self.$guard()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .serialize(head)
+            }
+
+            fn try_serialize(
+                &self,
+                buffer: &mut [u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), SerializeError> {
+                let (head, tail) = buffer.split_arr_mut();
+                debug_assert_eq!(0, tail.len());
+                self.$guard()
+                    .map_err(|_| SerializeError::Poisoned)?
+                    .try_serialize(head)
+            }
+        }
+
+        impl<T: Deserialize> Deserialize for $lock<T>
+        where
+            [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+        {
+            type Error = <T as Deserialize>::Error;
+
+            fn validate(
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                T::validate(head)
+            }
+
+            fn deserialize_in_place(
+                &mut self,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                self.get_mut()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .deserialize_in_place(head)
+            }
+
+            fn deserialize_in_place_unchecked(
+                &mut self,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                self.get_mut()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .deserialize_in_place_unchecked(head)
+            }
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                let (head, tail) = buffer.split_arr();
+                debug_assert_eq!(0, tail.len());
+                let value = T::deserialize(head)?;
+                unsafe {
+                    // Safety:
+                    // `into` is valid for writes
+                    into.write($lock::new(value));
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_for_lock!(Mutex, lock);
+impl_for_lock!(RwLock, read);
+
+#[cfg(test)]
+mod tests {
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize, SerializeError};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::{Mutex, RwLock};
+
+    #[test]
+    fn test_mutex() {
+        let mutex = Mutex::new((7u32, Some(true)));
+        let mut buf = [0; <Mutex<(u32, Option<bool>)> as SerialSize>::SIZE];
+        mutex.serialize(&mut buf);
+
+        let de = Mutex::<(u32, Option<bool>)>::deserialize(&buf)
+            .expect("deserialization failed");
+        assert_eq!(*mutex.lock().unwrap(), de.into_inner().unwrap());
+    }
+
+    #[test]
+    fn test_rw_lock() {
+        let lock = RwLock::new(-1.5f64);
+        let mut buf = [0; <RwLock<f64> as SerialSize>::SIZE];
+
+        let read = lock.read().unwrap();
+        lock.try_serialize(&mut buf).expect("serialization failed");
+        drop(read);
+
+        let de = RwLock::<f64>::deserialize(&buf).expect("deserialization failed");
+        assert_eq!(-1.5, de.into_inner().unwrap());
+    }
+
+    #[test]
+    fn test_poisoned() {
+        let mutex = Mutex::new(0u8);
+        let lock = RwLock::new(0u8);
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = mutex.lock().unwrap();
+            panic!("poisoning the mutex");
+        }));
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = lock.write().unwrap();
+            panic!("poisoning the lock");
+        }));
+
+        let mut buf = [0; 1];
+        mutex.serialize(&mut buf);
+        assert_eq!(Err(SerializeError::Poisoned), mutex.try_serialize(&mut buf));
+        assert_eq!(Err(SerializeError::Poisoned), lock.try_serialize(&mut buf));
+    }
+
+    #[test]
+    fn test_nested() {
+        let inner = |lock: &Mutex<u32>| *lock.lock().unwrap();
+        assert_serial_eq!(
+            (Mutex<u32>, u8),
+            &(Mutex::new(0x1234_5678), 9),
+            |(lock, byte): &(Mutex<u32>, u8)| (inner(lock), *byte)
+        );
+        assert_serial_eq!(
+            [Mutex<u32>; 3],
+            &[Mutex::new(1), Mutex::new(2), Mutex::new(3)],
+            |locks: &[Mutex<u32>; 3]| locks.each_ref().map(inner)
+        );
+        assert_serial_eq!(
+            Option<RwLock<bool>>,
+            &Some(RwLock::new(true)),
+            |lock: &Option<RwLock<bool>>| lock.as_ref().map(|lock| *lock.read().unwrap())
+        );
+
+        let poisoned = (7u8, [Mutex::new(1u16), Mutex::new(2u16)]);
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _guard = poisoned.1[1].lock().unwrap();
+            panic!("poisoning the mutex");
+        }));
+        let mut buf = [0; <(u8, [Mutex<u16>; 2]) as SerialSize>::SIZE];
+        assert_eq!(
+            Err(SerializeError::Poisoned),
+            poisoned.try_serialize(&mut buf)
+        );
+    }
+}
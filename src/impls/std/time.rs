@@ -0,0 +1,140 @@
+use crate::impls::core::time::NANOS_PER_SEC;
+use crate::schema::{sequence, Layout, Schema};
+use crate::{Deserialize, SerialSize, Serialize};
+use core::ptr::NonNull;
+use core::time::Duration;
+use split_array::SplitArray;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A [`SystemTime`] is serialized as a signed offset from [`UNIX_EPOCH`]:
+/// whole seconds as an `i64`, rounded towards negative infinity,
+/// followed by the non-negative sub-second remainder as `u32` nanoseconds.
+impl SerialSize for SystemTime {
+    const SIZE: usize = <i64 as SerialSize>::SIZE + <u32 as SerialSize>::SIZE;
+}
+
+/// The layout does not capture that `nanos` must be below one second.
+impl Schema for SystemTime {
+    const SCHEMA: Layout = Layout::structure(
+        "SystemTime",
+        &sequence(0, [("secs", &i64::SCHEMA), ("nanos", &u32::SCHEMA)]),
+    );
+}
+
+/// Serialization cannot fail: times further from the epoch than `i64` seconds,
+/// which only platforms with a wider [`SystemTime`] can represent,
+/// are clamped to `i64::MAX` or `i64::MIN` seconds and do not round-trip.
+impl Serialize for SystemTime {
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (secs, nanos) = match self.duration_since(UNIX_EPOCH) {
+            | Ok(after) => (
+                i64::try_from(after.as_secs()).unwrap_or(i64::MAX),
+                after.subsec_nanos(),
+            ),
+            | Err(before) => {
+                let before = before.duration();
+                let secs = 0i64.saturating_sub_unsigned(before.as_secs());
+                match before.subsec_nanos() {
+                    | 0 => (secs, 0),
+                    | nanos => (secs.saturating_sub(1), NANOS_PER_SEC - nanos),
+                }
+            }
+        };
+
+        let (secs_buffer, nanos_buffer) = buffer.split_arr_mut();
+        secs.serialize(secs_buffer);
+        nanos.serialize(nanos_buffer);
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DeserializeSystemTimeError {
+    /// The sub-second part is not below one second.
+    IllegalNanos,
+    /// The time cannot be represented by this platform's [`SystemTime`].
+    OutOfRange,
+}
+
+/// Whether a time is in range depends on the platform's [`SystemTime`],
+/// so [`validate`](Deserialize::validate) has to compute it, too.
+impl Deserialize for SystemTime {
+    type Error = DeserializeSystemTimeError;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        from_buffer(buffer).map(drop)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        into.write(from_buffer(buffer)?);
+        Ok(())
+    }
+}
+
+fn from_buffer(
+    buffer: &[u8; <SystemTime as SerialSize>::SIZE],
+) -> Result<SystemTime, DeserializeSystemTimeError> {
+    let (secs, nanos) = buffer.split_arr();
+    let secs = i64::deserialize(secs).unwrap();
+    let nanos = u32::deserialize(nanos).unwrap();
+    if nanos >= NANOS_PER_SEC {
+        return Err(DeserializeSystemTimeError::IllegalNanos);
+    }
+
+    let nanos = Duration::from_nanos(nanos.into());
+    let time = match u64::try_from(secs) {
+        | Ok(secs) => UNIX_EPOCH.checked_add(Duration::from_secs(secs) + nanos),
+        | Err(_) => UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))
+            .and_then(|time| time.checked_add(nanos)),
+    };
+
+    time.ok_or(DeserializeSystemTimeError::OutOfRange)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeserializeSystemTimeError;
+    use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+    use core::time::Duration;
+    use split_array::SplitArray;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn test_system_time() {
+        assert_serial_eq!(SystemTime, &UNIX_EPOCH);
+        let now = SystemTime::now();
+        assert_serial_eq!(SystemTime, &now);
+        assert_serial_eq!(SystemTime, &(UNIX_EPOCH + Duration::new(1, 500_000_000)));
+        assert_serial_eq!(SystemTime, &(UNIX_EPOCH - Duration::new(1, 500_000_000)));
+        assert_serial_eq!(SystemTime, &(UNIX_EPOCH - Duration::new(2, 0)));
+    }
+
+    #[test]
+    fn test_before_epoch() {
+        let mut buf = [0; <SystemTime as SerialSize>::SIZE];
+        (UNIX_EPOCH - Duration::new(1, 250_000_000)).serialize(&mut buf);
+
+        let (secs, nanos): (&[u8; <i64 as SerialSize>::SIZE], _) = buf.split_arr();
+        assert_eq!(Ok(-2), i64::deserialize(secs));
+        assert_eq!(Ok(750_000_000), u32::deserialize(nanos));
+    }
+
+    #[test]
+    fn test_illegal_nanos() {
+        let mut buf = [0; <SystemTime as SerialSize>::SIZE];
+        let (_, nanos): (&mut [u8; <i64 as SerialSize>::SIZE], _) = buf.split_arr_mut();
+        u32::MAX.serialize(nanos);
+
+        assert_eq!(
+            Err(DeserializeSystemTimeError::IllegalNanos),
+            SystemTime::deserialize(&buf)
+        );
+        assert_eq!(
+            Err(DeserializeSystemTimeError::IllegalNanos),
+            SystemTime::validate(&buf)
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! An order-preserving ("memcomparable") encoding.
+//!
+//! The serialized form of a [`Key<T>`] compares byte-wise like the `T` it was created from,
+//! which makes it suitable as a key in sorted key-value stores:
+//! - integers are big-endian, with the sign bit of signed integers flipped
+//! - floats are ordered like [`f64::total_cmp`]
+//! - `false < true` and `None < Some(_)`
+//! - tuples and arrays compare lexicographically
+//! - [`Reverse`] inverts the order of its contents
+//!
+//! A key has the same size as the regular serialized form of `T`
+//! and is valid whenever the regular serialized form is.
+
+use crate::{Deserialize, SerialSize, Serialize};
+use core::cmp::{Ordering, Reverse};
+use core::ptr::NonNull;
+use split_array::SplitArray;
+
+/// A type with an order-preserving encoding.
+///
+/// The key encoding is a reversible transformation of the regular serialized form.
+pub trait KeyEncoding: Serialize + Deserialize {
+    /// Turn the regular serialized form of a `Self` in `buffer` into its key encoding.
+    fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]);
+
+    /// Turn the key encoding in `buffer` back into the regular serialized form.
+    /// `buffer` may hold an invalid key, which must then turn into an invalid regular form.
+    fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]);
+}
+
+/// A `T` serialized in its order-preserving key encoding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+#[repr(transparent)]
+pub struct Key<T>(pub T);
+
+impl<T> Key<T> {
+    /// View a reference to a value as a reference to its key.
+    pub fn from_ref(value: &T) -> &Self {
+        // Safety:
+        // `Self` is `repr(transparent)`
+        unsafe { &*(value as *const T).cast::<Self>() }
+    }
+
+    /// Unwrap the value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: SerialSize> SerialSize for Key<T> {
+    const SIZE: usize = <T as SerialSize>::SIZE;
+}
+
+impl<T: KeyEncoding> Serialize for Key<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        self.0.serialize(head);
+        T::encode_key(head);
+    }
+}
+
+impl<T: KeyEncoding> Deserialize for Key<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    type Error = <T as Deserialize>::Error;
+
+    fn validate(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        let mut buffer = *head;
+        T::decode_key(&mut buffer);
+        T::validate(&buffer)
+    }
+
+    unsafe fn deserialize_raw(
+        into: NonNull<Self>,
+        buffer: &[u8; <Self as SerialSize>::SIZE],
+    ) -> Result<(), Self::Error> {
+        let (head, tail) = buffer.split_arr();
+        debug_assert_eq!(0, tail.len());
+        let mut buffer = *head;
+        T::decode_key(&mut buffer);
+        // Safety:
+        // `Key<T>` is `repr(transparent)`
+        unsafe { T::deserialize_raw(into.cast(), &buffer) }
+    }
+}
+
+macro_rules! impl_key_encoding_for_unsigned {
+    ($($ty:ty),*) => {
+        $(
+        impl KeyEncoding for $ty {
+            fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                *buffer = <$ty>::deserialize(buffer).unwrap().to_be_bytes();
+            }
+
+            fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                <$ty>::from_be_bytes(*buffer).serialize(buffer);
+            }
+        }
+        )*
+    };
+}
+
+macro_rules! impl_key_encoding_for_signed {
+    ($($ty:ty => $unsigned:ty),*) => {
+        $(
+        impl KeyEncoding for $ty {
+            fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                let value = <$ty>::deserialize(buffer).unwrap() as $unsigned;
+                *buffer = (value ^ !(<$unsigned>::MAX >> 1)).to_be_bytes();
+            }
+
+            fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                let value = <$unsigned>::from_be_bytes(*buffer) ^ !(<$unsigned>::MAX >> 1);
+                (value as $ty).serialize(buffer);
+            }
+        }
+        )*
+    };
+}
+
+macro_rules! impl_key_encoding_for_float {
+    ($($ty:ty => $bits:ty),*) => {
+        $(
+        /// Floats are ordered like [`total_cmp`](f64::total_cmp):
+        /// negative NaNs, negative numbers, `-0.0`, `0.0`, positive numbers, positive NaNs.
+        impl KeyEncoding for $ty {
+            fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                const SIGN: $bits = !(<$bits>::MAX >> 1);
+                let bits = <$ty>::deserialize(buffer).unwrap().to_bits();
+                let key = match bits & SIGN {
+                    | 0 => bits | SIGN,
+                    | _ => !bits,
+                };
+                *buffer = key.to_be_bytes();
+            }
+
+            fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                const SIGN: $bits = !(<$bits>::MAX >> 1);
+                let key = <$bits>::from_be_bytes(*buffer);
+                let bits = match key & SIGN {
+                    | 0 => !key,
+                    | _ => key & !SIGN,
+                };
+                <$ty>::from_bits(bits).serialize(buffer);
+            }
+        }
+        )*
+    };
+}
+
+macro_rules! impl_key_encoding_for_nonzero {
+    ($($nonzero:ty => $primitive:ty),*) => {
+        $(
+        impl KeyEncoding for $nonzero {
+            fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                <$primitive>::encode_key(buffer)
+            }
+
+            fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                <$primitive>::decode_key(buffer)
+            }
+        }
+        )*
+    };
+}
+
+impl_key_encoding_for_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_key_encoding_for_signed!(
+    i8 => u8,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    i128 => u128,
+    isize => usize
+);
+impl_key_encoding_for_float!(f32 => u32, f64 => u64);
+impl_key_encoding_for_nonzero!(
+    core::num::NonZeroU8 => u8,
+    core::num::NonZeroU16 => u16,
+    core::num::NonZeroU32 => u32,
+    core::num::NonZeroU64 => u64,
+    core::num::NonZeroU128 => u128,
+    core::num::NonZeroUsize => usize,
+    core::num::NonZeroI8 => i8,
+    core::num::NonZeroI16 => i16,
+    core::num::NonZeroI32 => i32,
+    core::num::NonZeroI64 => i64,
+    core::num::NonZeroI128 => i128,
+    core::num::NonZeroIsize => isize
+);
+
+/// `false` and `true` are already serialized as `0` and `1`.
+impl KeyEncoding for bool {
+    fn encode_key(_buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+
+    fn decode_key(_buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+}
+
+impl KeyEncoding for char {
+    fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        u32::encode_key(buffer)
+    }
+
+    fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        u32::decode_key(buffer)
+    }
+}
+
+impl KeyEncoding for Ordering {
+    fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        i8::encode_key(buffer)
+    }
+
+    fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        i8::decode_key(buffer)
+    }
+}
+
+impl KeyEncoding for () {
+    fn encode_key(_buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+
+    fn decode_key(_buffer: &mut [u8; <Self as SerialSize>::SIZE]) {}
+}
+
+/// The tag is kept as is, so that `None < Some(_)`.
+/// The payload of `None` is all zeros in both encodings.
+impl<T: KeyEncoding> KeyEncoding for Option<T>
+where
+    [(); <Self as SerialSize>::SIZE - <bool as SerialSize>::SIZE]:,
+    [(); <Self as SerialSize>::SIZE
+        - <bool as SerialSize>::SIZE
+        - <T as SerialSize>::SIZE]:,
+{
+    fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (tag, buffer): (&mut [u8; <bool as SerialSize>::SIZE], _) =
+            buffer.split_arr_mut();
+        let (payload, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        if *tag == [1] {
+            T::encode_key(payload)
+        }
+    }
+
+    fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (tag, buffer): (&mut [u8; <bool as SerialSize>::SIZE], _) =
+            buffer.split_arr_mut();
+        let (payload, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        if *tag == [1] {
+            T::decode_key(payload)
+        }
+    }
+}
+
+impl<T: KeyEncoding, const LEN: usize> KeyEncoding for [T; LEN]
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        if <T as SerialSize>::SIZE == 0 {
+            return;
+        }
+        buffer.array_chunks_mut().for_each(T::encode_key)
+    }
+
+    fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        if <T as SerialSize>::SIZE == 0 {
+            return;
+        }
+        buffer.array_chunks_mut().for_each(T::decode_key)
+    }
+}
+
+/// Every byte of the key of the contained value is inverted, which reverses the order.
+impl<T: KeyEncoding> KeyEncoding for Reverse<T>
+where
+    [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+{
+    fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        T::encode_key(head);
+        head.iter_mut().for_each(|byte| *byte = !*byte);
+    }
+
+    fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        let (head, tail) = buffer.split_arr_mut();
+        debug_assert_eq!(0, tail.len());
+        head.iter_mut().for_each(|byte| *byte = !*byte);
+        T::decode_key(head);
+    }
+}
+
+// invoked by `impl_for_tuples!`
+macro_rules! impl_for_tuple {
+    (($($fields:tt: $ts:ident,)*)) => {
+        impl<$($ts: KeyEncoding,)*> KeyEncoding for ($($ts,)*)
+        where
+            [(); <($($ts,)*) as SerialSize>::SIZE $(- <$ts as SerialSize>::SIZE)*]:, {
+
+            #[allow(unused_variables)]
+            fn encode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                $(
+                let (head, buffer) = split_array::split_arr_mut(buffer);
+                <$ts as KeyEncoding>::encode_key(head);
+                )*
+            }
+
+            #[allow(unused_variables)]
+            fn decode_key(buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                $(
+                let (head, buffer) = split_array::split_arr_mut(buffer);
+                <$ts as KeyEncoding>::decode_key(head);
+                )*
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "large_tuples"))]
+arrise_macro::impl_for_tuples!(12);
+#[cfg(feature = "large_tuples")]
+arrise_macro::impl_for_tuples!(64);
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Key, KeyEncoding};
+    use crate::{assert_serial_eq, SerialSize, Serialize};
+    use core::cmp::{Ordering, Reverse};
+    use core::fmt::Debug;
+
+    /// A xorshift generator, so that the tests are reproducible.
+    pub(crate) struct Rng(pub(crate) u64);
+
+    impl Rng {
+        pub(crate) fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+    }
+
+    fn key_bytes<T: KeyEncoding>(value: &T) -> [u8; <Key<T> as SerialSize>::SIZE]
+    where
+        [(); <Key<T> as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+    {
+        Key::from_ref(value).to_bytes()
+    }
+
+    /// Check `cmp(a, b) == cmp(key(a), key(b))` for all pairs of `values`,
+    /// and that every key deserializes to its value.
+    fn assert_order<T: KeyEncoding + Debug>(
+        values: &[T],
+        cmp: impl Fn(&T, &T) -> Ordering,
+    ) where
+        [(); <Key<T> as SerialSize>::SIZE - <T as SerialSize>::SIZE]:,
+    {
+        for a in values {
+            for b in values {
+                assert_eq!(
+                    cmp(a, b),
+                    key_bytes(a).cmp(&key_bytes(b)),
+                    "{a:?} <=> {b:?}"
+                );
+            }
+            let de = <Key<T> as crate::Deserialize>::deserialize(&key_bytes(a));
+            assert_eq!(Some(Ordering::Equal), de.ok().map(|de| cmp(a, &de.0)));
+        }
+    }
+
+    #[test]
+    fn test_integers() {
+        let mut rng = Rng(0x2545f4914f6cdd1d);
+        let mut values: [i64; 64] = core::array::from_fn(|_| rng.next() as i64);
+        values[..6].copy_from_slice(&[0, 1, -1, i64::MIN, i64::MAX, i64::MIN + 1]);
+        assert_order(&values, Ord::cmp);
+        assert_order(&values.map(|x| x as u64), Ord::cmp);
+        assert_order(&values.map(|x| x as i16), Ord::cmp);
+        assert_order(&values.map(|x| x as u8), Ord::cmp);
+        assert_order(&values.map(|x| x as i128 * x as i128), Ord::cmp);
+    }
+
+    #[test]
+    fn test_floats() {
+        let mut rng = Rng(0x9e3779b97f4a7c15);
+        let mut values: [f64; 64] = core::array::from_fn(|_| f64::from_bits(rng.next()));
+        values[..8].copy_from_slice(&[
+            0.0,
+            -0.0,
+            1.5,
+            -1.5,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+            -f64::NAN,
+        ]);
+        assert_order(&values, f64::total_cmp);
+        assert_order(&values.map(|x| x as f32), f32::total_cmp);
+    }
+
+    #[test]
+    fn test_compound() {
+        let mut rng = Rng(0xdeadbeefcafebabe);
+        let values: [(i8, Option<u16>, bool); 64] = core::array::from_fn(|_| {
+            let x = rng.next();
+            (
+                (x >> 60) as i8 - 8,
+                (x & 1 == 0).then_some((x >> 8) as u16 % 4),
+                x & 2 == 0,
+            )
+        });
+        assert_order(&values, Ord::cmp);
+
+        let values: [[Option<i8>; 2]; 64] = core::array::from_fn(|_| {
+            let x = rng.next();
+            [
+                (x & 1 == 0).then_some(x as i8 >> 6),
+                Some((x >> 8) as i8 >> 6),
+            ]
+        });
+        assert_order(&values, Ord::cmp);
+
+        let values: [(Reverse<u16>, char, Ordering); 64] = core::array::from_fn(|_| {
+            let x = rng.next();
+            (
+                Reverse(x as u16 % 8),
+                char::from_u32((x >> 16) as u32 % 0x11_0000).unwrap_or('?'),
+                (x >> 60).cmp(&8),
+            )
+        });
+        assert_order(&values, Ord::cmp);
+    }
+
+    #[test]
+    fn test_encoding() {
+        assert_eq!([0x80, 0x00, 0x00, 0x01], key_bytes(&1i32));
+        assert_eq!([0x7f, 0xff], key_bytes(&-1i16));
+        assert_eq!([0x00, 0x00, 0x01, 0x02], key_bytes(&0x0102u32));
+        assert_eq!([1, 0x81], key_bytes(&Some(1i8)));
+        assert_eq!([0, 0], key_bytes(&None::<i8>));
+
+        assert_serial_eq!(Key<(u32, Option<f32>)>, &Key((7, Some(-2.5))));
+        assert_serial_eq!(Key<[Reverse<i64>; 2]>, &Key([Reverse(-3), Reverse(4)]));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut buf = [0; <Key<(bool, char)> as SerialSize>::SIZE];
+        Key((true, 'a')).serialize(&mut buf);
+        buf[0] = 2;
+        assert!(<Key<(bool, char)> as crate::Deserialize>::validate(&buf).is_err());
+
+        // a key encoding of a surrogate
+        let buf = [1, 0x00, 0x00, 0xd8, 0x00];
+        assert!(<Key<Option<char>> as crate::Deserialize>::deserialize(&buf).is_err());
+    }
+}
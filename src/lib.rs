@@ -8,11 +8,26 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![feature(cfg_target_has_atomic)]
 #![cfg_attr(feature = "atomic_int_128", feature(integer_atomics))]
+#![cfg_attr(feature = "alloc", feature(new_uninit))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+// lets `#[bounds]` refer to `::arrise` from within this crate
+extern crate self as arrise;
 
 use core::mem::MaybeUninit;
 use core::ptr::NonNull;
 
+#[cfg(feature = "std")]
+pub mod file;
 pub mod impls;
+pub mod key;
+pub mod plain;
+pub mod record;
+pub mod schema;
+pub mod serialized;
+
+pub use arrise_bounds::bounds;
 
 /// A trait indicating the size of the serialized form of `Self`.
 pub trait SerialSize {
@@ -24,6 +39,101 @@ pub trait SerialSize {
 pub trait Serialize: SerialSize {
     /// Serialize `self` into the provided `buffer`.
     fn serialize(&self, buffer: &mut [u8; Self::SIZE]);
+
+    /// Serialize `self` into the uninitialized `buffer`.
+    /// Returns a mutable reference to the now initialized contents of `buffer`.
+    ///
+    /// The default implementation zeroes `buffer` before serializing into it.
+    /// Types that write every byte, padding included, override this to skip the zeroing.
+    ///
+    /// Overrides should return `buffer` itself.
+    /// Callers within this crate do not rely on that for soundness, though:
+    /// if the returned reference points elsewhere, they copy its contents into `buffer`.
+    fn serialize_uninit<'a>(
+        &self,
+        buffer: &'a mut MaybeUninit<[u8; Self::SIZE]>,
+    ) -> &'a mut [u8; Self::SIZE] {
+        let buffer = buffer.write([0; Self::SIZE]);
+        self.serialize(buffer);
+        buffer
+    }
+
+    /// Serialize `self` into the provided `buffer`, returning an error instead of
+    /// panicking if a part of `self` cannot be accessed,
+    /// such as a mutably borrowed [`RefCell`](core::cell::RefCell).
+    /// If this function returns `Err`, the contents of `buffer` are unspecified.
+    ///
+    /// The default implementation calls [`serialize`](Self::serialize) and never fails.
+    /// Types containing other values override this to forward to each of them.
+    fn try_serialize(&self, buffer: &mut [u8; Self::SIZE]) -> Result<(), SerializeError> {
+        self.serialize(buffer);
+        Ok(())
+    }
+
+    /// Serialize `self` into a new buffer.
+    fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut buffer = MaybeUninit::uninit();
+        impls::serialize_uninit_into(self, &mut buffer);
+        // Safety:
+        // `serialize_uninit_into` just initialized `buffer`
+        unsafe { buffer.assume_init() }
+    }
+
+    /// Serialize all `values` into consecutive chunks of `buffer`.
+    /// `buffer` must be exactly `values.len() * Self::SIZE` bytes long.
+    ///
+    /// The array impls forward to this, so that e.g. primitives can handle
+    /// a whole array with a single copy. It is not meant to be called directly.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn serialize_slice(values: &[Self], buffer: &mut [u8])
+    where
+        Self: Sized,
+        [(); Self::SIZE]:,
+    {
+        debug_assert_eq!(values.len() * Self::SIZE, buffer.len());
+        if Self::SIZE == 0 {
+            return;
+        }
+        for (value, buffer) in values.iter().zip(buffer.array_chunks_mut()) {
+            value.serialize(buffer)
+        }
+    }
+
+    /// Fallibly serialize all `values` into consecutive chunks of `buffer`,
+    /// like [`serialize_slice`](Self::serialize_slice).
+    #[doc(hidden)]
+    #[inline(always)]
+    fn try_serialize_slice(
+        values: &[Self],
+        buffer: &mut [u8],
+    ) -> Result<(), SerializeError>
+    where
+        Self: Sized,
+        [(); Self::SIZE]:,
+    {
+        debug_assert_eq!(values.len() * Self::SIZE, buffer.len());
+        if Self::SIZE == 0 {
+            for value in values {
+                value.try_serialize((&mut [][..]).try_into().unwrap())?;
+            }
+            return Ok(());
+        }
+        for (value, buffer) in values.iter().zip(buffer.array_chunks_mut()) {
+            value.try_serialize(buffer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The error returned by [`Serialize::try_serialize`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SerializeError {
+    /// A [`RefCell`](core::cell::RefCell) is currently mutably borrowed.
+    Borrowed,
+    /// A lock was poisoned by a thread panicking while holding it.
+    Poisoned,
 }
 
 /// Deserialize `Self` from a const-sized byte buffer directly into a pre-allocated [`MaybeUninit<Self>`].
@@ -39,6 +149,16 @@ pub trait Deserialize: SerialSize + Sized {
         Ok(unsafe { result.assume_init() })
     }
 
+    /// Check whether `buffer` holds a valid serialized `Self`, without constructing it.
+    /// Returns the same error [`deserialize`](Self::deserialize) would.
+    ///
+    /// The default implementation deserializes a `Self` and drops it.
+    /// Types whose buffers can be checked without building a value override this,
+    /// and may rely on it in their own deserialization.
+    fn validate(buffer: &[u8; Self::SIZE]) -> Result<(), Self::Error> {
+        Self::deserialize(buffer).map(drop)
+    }
+
     /// Deserialize a `Self` from the provided `buffer`.
     /// Returns a mutable refernce to the initialized contents of `into`.
     ///
@@ -60,6 +180,38 @@ pub trait Deserialize: SerialSize + Sized {
         }
     }
 
+    /// Deserialize the provided `buffer` into `self`, replacing its previous value.
+    /// If this function returns `Err`, `self` is left untouched.
+    ///
+    /// The default implementation deserializes into a temporary and then moves it into `self`.
+    /// Compound types override this to validate their whole `buffer` first
+    /// and then update each of their parts in place.
+    fn deserialize_in_place(
+        &mut self,
+        buffer: &[u8; Self::SIZE],
+    ) -> Result<(), Self::Error> {
+        *self = Self::deserialize(buffer)?;
+        Ok(())
+    }
+
+    /// Deserialize the provided `buffer`, which has already passed
+    /// [`validate`](Self::validate), into `self`.
+    /// If `buffer` is invalid after all, `self` may be left partially updated.
+    ///
+    /// Compound types validate their whole `buffer` once and then call this
+    /// on their parts, so that nested buffers are not validated again at every level.
+    /// It is not meant to be called directly.
+    ///
+    /// The default implementation forwards to
+    /// [`deserialize_in_place`](Self::deserialize_in_place).
+    #[doc(hidden)]
+    fn deserialize_in_place_unchecked(
+        &mut self,
+        buffer: &[u8; Self::SIZE],
+    ) -> Result<(), Self::Error> {
+        self.deserialize_in_place(buffer)
+    }
+
     /// Deserialize a `Self` from the provided `buffer`.
     /// If this function returns `Ok`, `into` will contain a valid `Self`.
     /// Returns a mutable refernce to the initialized contents of `into`.
@@ -71,6 +223,43 @@ pub trait Deserialize: SerialSize + Sized {
         into: NonNull<Self>,
         buffer: &[u8; Self::SIZE],
     ) -> Result<(), Self::Error>;
+
+    /// Deserialize `len` consecutive values from `buffer` into `into`.
+    /// On error, the values deserialized so far are leaked.
+    ///
+    /// The array impls forward to this, so that e.g. primitives can handle
+    /// a whole array with a single copy. It is not meant to be called directly.
+    ///
+    /// # Safety
+    /// - `into` must be valid for writes of `len` consecutive values
+    /// - `buffer` must be exactly `len * Self::SIZE` bytes long
+    #[doc(hidden)]
+    #[inline(always)]
+    unsafe fn deserialize_raw_slice(
+        into: NonNull<Self>,
+        len: usize,
+        buffer: &[u8],
+    ) -> Result<(), Self::Error>
+    where
+        [(); Self::SIZE]:,
+    {
+        debug_assert_eq!(len * Self::SIZE, buffer.len());
+        if Self::SIZE == 0 {
+            for i in 0..len {
+                // Safety:
+                // `i` is bounded by `len`
+                Self::deserialize_raw(into.add(i), (&[][..]).try_into().unwrap())?;
+            }
+            return Ok(());
+        }
+        for (i, buffer) in buffer.array_chunks().enumerate() {
+            // Safety:
+            // there are exactly `len` chunks
+            Self::deserialize_raw(into.add(i), buffer)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[allow(unused)]
@@ -91,3 +280,68 @@ macro_rules! assert_serial_eq {
 
 #[allow(unused)]
 use assert_serial_eq;
+
+#[cfg(test)]
+mod tests {
+    use crate::{bounds, Deserialize, SerialSize, Serialize};
+
+    #[bounds(Option<(T, u8)>)]
+    fn round_trip<T: Serialize + Deserialize>(value: T) -> Option<(T, u8)> {
+        let bytes = Some((value, 7)).to_bytes();
+        Option::<(T, u8)>::deserialize(&bytes).ok().flatten()
+    }
+
+    #[bounds(core::ops::Range<T>)]
+    fn range_is_valid<T: Serialize + Deserialize>(value: core::ops::Range<T>) -> bool {
+        core::ops::Range::<T>::validate(&value.to_bytes()).is_ok()
+    }
+
+    struct Framed<T>(T);
+
+    #[bounds]
+    impl<T: Serialize> Framed<T> {
+        fn to_bytes(&self) -> [u8; <T as SerialSize>::SIZE] {
+            self.0.to_bytes()
+        }
+    }
+
+    #[test]
+    fn test_bounds() {
+        assert_eq!(Some((0x1234u16, 7)), round_trip(0x1234u16));
+        assert_eq!(Some(([true, false], 7)), round_trip([true, false]));
+        assert_eq!(0x1234u16.to_bytes(), Framed(0x1234u16).to_bytes());
+        assert!(range_is_valid(3u8..7));
+    }
+
+    /// Returns leaked memory from `serialize_uninit` instead of `buffer`.
+    #[cfg(feature = "alloc")]
+    struct Elsewhere(u8);
+
+    #[cfg(feature = "alloc")]
+    impl SerialSize for Elsewhere {
+        const SIZE: usize = 2;
+    }
+
+    #[cfg(feature = "alloc")]
+    impl Serialize for Elsewhere {
+        fn serialize(&self, buffer: &mut [u8; 2]) {
+            *buffer = [self.0, !self.0];
+        }
+
+        fn serialize_uninit<'a>(
+            &self,
+            _buffer: &'a mut core::mem::MaybeUninit<[u8; 2]>,
+        ) -> &'a mut [u8; 2] {
+            alloc::boxed::Box::leak(alloc::boxed::Box::new([self.0, !self.0]))
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_serialize_uninit_elsewhere() {
+        assert_eq!([1, 0xfe], Elsewhere(1).to_bytes());
+        assert_eq!([1, 0xfe, 2, 0xfd], [Elsewhere(1), Elsewhere(2)].to_bytes());
+        assert_eq!([1, 0xfe, 7], (Elsewhere(1), 7u8).to_bytes());
+        assert_eq!([1, 3, 0xfc], Some(Elsewhere(3)).to_bytes());
+    }
+}
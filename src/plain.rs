@@ -0,0 +1,305 @@
+//! Types whose serialized form is exactly their in-memory representation.
+//!
+//! For these, (de-)serialization is a plain copy, and a suitably aligned buffer
+//! can be borrowed as a value directly.
+
+use crate::{Deserialize, SerialSize, Serialize};
+use core::cmp::Reverse;
+use core::mem::{size_of, size_of_val};
+use core::num::{Saturating, Wrapping};
+use core::ptr::{self, NonNull};
+use core::slice;
+
+/// A type whose serialized form is byte-for-byte its in-memory representation.
+///
+/// Primitive numbers only implement this trait if the `primitive_le` and `primitive_be`
+/// features select the native byte order.
+///
+/// A `repr(C)` struct whose fields all implement `PlainLayout` may implement it too,
+/// provided that it has no padding and that its [`Serialize`] and [`Deserialize`] impls
+/// agree with its layout, e.g. by forwarding to
+/// [`serialize_plain`](Self::serialize_plain) and [`deserialize_plain`](Self::deserialize_plain).
+/// Forwarding `Serialize::serialize_slice` and `Deserialize::deserialize_raw_slice`
+/// to [`serialize_plain_slice`](Self::serialize_plain_slice) and
+/// [`deserialize_plain_slice`](Self::deserialize_plain_slice) as well
+/// lets arrays of the struct be (de-)serialized with a single copy, like arrays of primitives.
+///
+/// # Safety
+/// - `<Self as SerialSize>::SIZE` equals `size_of::<Self>()`
+/// - `Self` has no padding, so that every byte of a value is initialized
+/// - every bit pattern of `[u8; SIZE]` is a valid `Self`
+/// - [`Serialize::serialize`] writes the bytes of `self`
+///   and [`Deserialize::deserialize`] reads them back without failing
+pub unsafe trait PlainLayout: Serialize + Deserialize + Copy {
+    /// The serialized form of `self`, borrowed from `self`.
+    fn as_bytes(&self) -> &[u8; <Self as SerialSize>::SIZE] {
+        debug_assert_eq!(size_of::<Self>(), <Self as SerialSize>::SIZE);
+        // Safety:
+        // `Self` consists of `SIZE` initialized bytes without padding
+        unsafe { &*(self as *const Self).cast() }
+    }
+
+    /// Serialize `self` into `buffer` with a single copy.
+    #[inline(always)]
+    fn serialize_plain(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+        *buffer = *self.as_bytes();
+    }
+
+    /// Deserialize a `Self` from `buffer` with a single copy.
+    #[inline(always)]
+    fn deserialize_plain(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Self {
+        debug_assert_eq!(size_of::<Self>(), <Self as SerialSize>::SIZE);
+        // Safety:
+        // - `buffer` is valid for reads of `SIZE` bytes, which is the size of `Self`
+        // - any bit pattern is a valid `Self`
+        unsafe { ptr::read_unaligned(buffer.as_ptr().cast()) }
+    }
+
+    /// Serialize all `values` into `buffer` with a single copy.
+    /// `buffer` must be exactly `values.len() * SIZE` bytes long.
+    #[inline(always)]
+    fn serialize_plain_slice(values: &[Self], buffer: &mut [u8]) {
+        debug_assert_eq!(size_of_val(values), buffer.len());
+        // Safety:
+        // `Self` has no padding, so all bytes of `values` are initialized
+        let bytes = unsafe {
+            slice::from_raw_parts(values.as_ptr().cast::<u8>(), size_of_val(values))
+        };
+        buffer.copy_from_slice(bytes);
+    }
+
+    /// Deserialize `len` consecutive values from `buffer` into `into` with a single copy.
+    ///
+    /// # Safety
+    /// - `into` must be valid for writes of `len` consecutive values
+    /// - `buffer` must be exactly `len * SIZE` bytes long
+    #[inline(always)]
+    unsafe fn deserialize_plain_slice(into: NonNull<Self>, len: usize, buffer: &[u8]) {
+        debug_assert_eq!(len * size_of::<Self>(), buffer.len());
+        // Safety:
+        // - `into` is valid for writes of `len` values, i.e. `buffer.len()` bytes
+        // - any bit pattern is a valid `Self`
+        ptr::copy_nonoverlapping(
+            buffer.as_ptr(),
+            into.as_ptr().cast::<u8>(),
+            buffer.len(),
+        );
+    }
+
+    /// Borrow `buffer` as a `Self`.
+    /// Returns `None` if `buffer` is not aligned for `Self`.
+    fn ref_from_bytes(buffer: &[u8; <Self as SerialSize>::SIZE]) -> Option<&Self> {
+        debug_assert_eq!(size_of::<Self>(), <Self as SerialSize>::SIZE);
+        let ptr = buffer.as_ptr().cast::<Self>();
+        // Safety:
+        // - `ptr` is aligned and valid for reads of `SIZE` bytes
+        // - any bit pattern is a valid `Self`
+        ptr.is_aligned().then(|| unsafe { &*ptr })
+    }
+
+    /// Mutably borrow `buffer` as a `Self`.
+    /// Returns `None` if `buffer` is not aligned for `Self`.
+    fn mut_from_bytes(
+        buffer: &mut [u8; <Self as SerialSize>::SIZE],
+    ) -> Option<&mut Self> {
+        debug_assert_eq!(size_of::<Self>(), <Self as SerialSize>::SIZE);
+        let ptr = buffer.as_mut_ptr().cast::<Self>();
+        // Safety:
+        // - `ptr` is aligned and valid for reads and writes of `SIZE` bytes
+        // - any bit pattern is a valid `Self`, and any `Self` is a valid byte array
+        ptr.is_aligned().then(|| unsafe { &mut *ptr })
+    }
+}
+
+/// Pass the `cfg` predicate for primitives being serialized in native byte order
+/// to `$callback`, after `$args`.
+/// The checks mirror the feature priority of the primitive impls.
+macro_rules! native_endian_predicate {
+    ([$($callback:tt)*] $($args:tt)*) => {
+        $($callback)*! {
+            $($args)*
+            any(
+                all(feature = "primitive_le", target_endian = "little"),
+                all(
+                    not(feature = "primitive_le"),
+                    feature = "primitive_be",
+                    target_endian = "big"
+                ),
+                not(any(feature = "primitive_le", feature = "primitive_be")),
+            )
+        }
+    };
+}
+
+/// Only emit the given items if primitives are serialized in native byte order.
+macro_rules! cfg_native_endian {
+    (@cfg [$($item:item)*] $predicate:meta) => {
+        $(
+        #[cfg($predicate)]
+        $item
+        )*
+    };
+    ($($item:item)*) => {
+        $crate::plain::native_endian_predicate! {
+            [$crate::plain::cfg_native_endian] @cfg [$($item)*]
+        }
+    };
+}
+
+/// Only emit the given items if primitives are serialized in non-native byte order.
+macro_rules! cfg_foreign_endian {
+    (@cfg [$($item:item)*] $predicate:meta) => {
+        $(
+        #[cfg(not($predicate))]
+        $item
+        )*
+    };
+    ($($item:item)*) => {
+        $crate::plain::native_endian_predicate! {
+            [$crate::plain::cfg_foreign_endian] @cfg [$($item)*]
+        }
+    };
+}
+
+pub(crate) use {cfg_foreign_endian, cfg_native_endian, native_endian_predicate};
+
+/// Whether primitives are serialized in native byte order.
+/// [`Endian::PRIMITIVE`](crate::schema::Endian::PRIMITIVE) is derived from this.
+pub(crate) const NATIVE_ENDIAN: bool = native_endian_predicate!([cfg]);
+
+macro_rules! impl_plain_layout {
+    ($($ty:ty),*) => {
+        cfg_native_endian! {
+            $(
+            // Safety:
+            // primitives are serialized with `to_ne_bytes`, or equivalent
+            unsafe impl PlainLayout for $ty {}
+            )*
+        }
+    };
+}
+
+impl_plain_layout!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64
+);
+
+// Safety:
+// the elements are laid out without gaps and serialized in order
+unsafe impl<T: PlainLayout, const LEN: usize> PlainLayout for [T; LEN] where
+    [(); <T as SerialSize>::SIZE]:
+{
+}
+
+macro_rules! impl_plain_layout_for_transparent {
+    ($($wrapper:ident),*) => {
+        $(
+        // Safety:
+        // `$wrapper<T>` is `repr(transparent)` and serialized as its `T`
+        unsafe impl<T: PlainLayout> PlainLayout for $wrapper<T> where
+            [(); <Self as SerialSize>::SIZE - <T as SerialSize>::SIZE]:
+        {
+        }
+        )*
+    };
+}
+
+impl_plain_layout_for_transparent!(Wrapping, Saturating, Reverse);
+
+cfg_native_endian! {
+    #[cfg(test)]
+    mod tests {
+        use super::PlainLayout;
+        use crate::{assert_serial_eq, Deserialize, SerialSize, Serialize};
+        use core::ptr::NonNull;
+
+        #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+        #[repr(C)]
+        struct Sample {
+            id: u32,
+            pos: [i16; 2],
+        }
+
+        impl SerialSize for Sample {
+            const SIZE: usize =
+                <u32 as SerialSize>::SIZE + <[i16; 2] as SerialSize>::SIZE;
+        }
+
+        impl Serialize for Sample {
+            fn serialize(&self, buffer: &mut [u8; <Self as SerialSize>::SIZE]) {
+                self.serialize_plain(buffer)
+            }
+
+            fn serialize_slice(values: &[Self], buffer: &mut [u8]) {
+                Self::serialize_plain_slice(values, buffer)
+            }
+        }
+
+        impl Deserialize for Sample {
+            type Error = core::convert::Infallible;
+
+            unsafe fn deserialize_raw(
+                into: NonNull<Self>,
+                buffer: &[u8; <Self as SerialSize>::SIZE],
+            ) -> Result<(), Self::Error> {
+                into.write(Self::deserialize_plain(buffer));
+                Ok(())
+            }
+
+            unsafe fn deserialize_raw_slice(
+                into: NonNull<Self>,
+                len: usize,
+                buffer: &[u8],
+            ) -> Result<(), Self::Error> {
+                Self::deserialize_plain_slice(into, len, buffer);
+                Ok(())
+            }
+        }
+
+        // Safety:
+        // `Sample` is `repr(C)` without padding and (de-)serialized as plain bytes
+        unsafe impl PlainLayout for Sample {}
+
+        #[repr(align(8))]
+        struct Aligned([u8; 16]);
+
+        #[test]
+        fn test_matches_serialize() {
+            let value = [0x01234567u32, 0x89abcdef];
+            let mut buf = [0; <[u32; 2] as SerialSize>::SIZE];
+            value.serialize(&mut buf);
+
+            assert_eq!(&buf, value.as_bytes());
+            assert_eq!(value, <[u32; 2]>::deserialize_plain(&buf));
+            assert_eq!(Ok(value), <[u32; 2]>::deserialize(&buf));
+        }
+
+        #[test]
+        fn test_struct() {
+            let sample = Sample { id: 7, pos: [-1, 2] };
+            assert_serial_eq!(Sample, &sample);
+            assert_serial_eq!([Sample; 2], &[sample; 2]);
+
+            let samples = [sample, Sample { id: 8, pos: [3, -4] }];
+            let mut buf = [0; <[Sample; 2] as SerialSize>::SIZE];
+            samples.serialize(&mut buf);
+            assert_eq!(&buf, samples.as_bytes());
+            assert_eq!(Ok(samples), <[Sample; 2]>::deserialize(&buf));
+        }
+
+        #[test]
+        fn test_ref_from_bytes() {
+            let mut aligned = Aligned([0; 16]);
+            let buf: &mut [u8; 8] = (&mut aligned.0[..8]).try_into().unwrap();
+            let value = u64::mut_from_bytes(buf).expect("buffer is aligned");
+            *value = 0x0102030405060708;
+            assert_eq!(0x0102030405060708u64.as_bytes(), &aligned.0[..8]);
+
+            let buf: &[u8; 8] = (&aligned.0[..8]).try_into().unwrap();
+            assert_eq!(Some(&0x0102030405060708), u64::ref_from_bytes(buf));
+
+            let buf: &[u8; 8] = (&aligned.0[1..9]).try_into().unwrap();
+            assert_eq!(None, u64::ref_from_bytes(buf));
+            assert!(u8::ref_from_bytes(&[3]).is_some());
+        }
+    }
+}
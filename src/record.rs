@@ -0,0 +1,302 @@
+//! Views of byte slices as sequences of fixed-size records.
+//!
+//! Each record is the `T::SIZE` byte serialized form of a `T`.
+//! Any bytes after the last whole record are kept apart as the remainder.
+
+use crate::{Deserialize, SerialSize, Serialize};
+use core::marker::PhantomData;
+use core::slice::ArrayChunks;
+
+/// A shared view of a byte slice as consecutive serialized `T`s.
+#[derive(Debug)]
+pub struct RecordSlice<'a, T: SerialSize> {
+    records: &'a [u8],
+    remainder: &'a [u8],
+    _type: PhantomData<fn() -> T>,
+}
+
+/// A mutable view of a byte slice as consecutive serialized `T`s.
+#[derive(Debug)]
+pub struct RecordSliceMut<'a, T: SerialSize> {
+    records: &'a mut [u8],
+    remainder: &'a mut [u8],
+    _type: PhantomData<fn() -> T>,
+}
+
+/// The number of bytes occupied by whole records in a slice of `len` bytes.
+const fn records_len<T: SerialSize>(len: usize) -> usize {
+    assert!(
+        <T as SerialSize>::SIZE != 0,
+        "records must not be zero-sized"
+    );
+    len - len % <T as SerialSize>::SIZE
+}
+
+impl<'a, T: SerialSize> Clone for RecordSlice<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T: SerialSize> Copy for RecordSlice<'a, T> {}
+
+impl<'a, T: SerialSize> RecordSlice<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// View `bytes` as records.
+    ///
+    /// # Panics
+    /// If `T` is zero-sized.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let (records, remainder) = bytes.split_at(records_len::<T>(bytes.len()));
+        Self {
+            records,
+            remainder,
+            _type: PhantomData,
+        }
+    }
+
+    /// The number of whole records.
+    pub fn len(&self) -> usize {
+        self.records.len() / <T as SerialSize>::SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The trailing bytes that do not form a whole record.
+    pub fn remainder(&self) -> &'a [u8] {
+        self.remainder
+    }
+
+    /// The serialized bytes of the record at `index`, or `None` if it is out of bounds.
+    pub fn get_bytes(&self, index: usize) -> Option<&'a [u8; <T as SerialSize>::SIZE]> {
+        let offset = index.checked_mul(<T as SerialSize>::SIZE)?;
+        self.records.get(offset..)?.first_chunk()
+    }
+
+    /// Iterate over the serialized bytes of all records.
+    pub fn iter_bytes(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &'a [u8; <T as SerialSize>::SIZE]> + ExactSizeIterator
+    {
+        self.records.array_chunks()
+    }
+}
+
+impl<'a, T: Deserialize> RecordSlice<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Deserialize the record at `index`, or return `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Result<T, T::Error>> {
+        self.get_bytes(index).map(T::deserialize)
+    }
+
+    /// Deserialize all records in order.
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter {
+            chunks: self.records.array_chunks(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: SerialSize> RecordSliceMut<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// View `bytes` as records.
+    ///
+    /// # Panics
+    /// If `T` is zero-sized.
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        let (records, remainder) = bytes.split_at_mut(records_len::<T>(bytes.len()));
+        Self {
+            records,
+            remainder,
+            _type: PhantomData,
+        }
+    }
+
+    /// Reborrow as a shared [`RecordSlice`].
+    pub fn as_record_slice(&self) -> RecordSlice<'_, T> {
+        RecordSlice {
+            records: self.records,
+            remainder: self.remainder,
+            _type: PhantomData,
+        }
+    }
+
+    /// The number of whole records.
+    pub fn len(&self) -> usize {
+        self.as_record_slice().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The trailing bytes that do not form a whole record.
+    pub fn remainder(&mut self) -> &mut [u8] {
+        self.remainder
+    }
+
+    /// The serialized bytes of the record at `index`, or `None` if it is out of bounds.
+    pub fn get_bytes(&self, index: usize) -> Option<&[u8; <T as SerialSize>::SIZE]> {
+        self.as_record_slice().get_bytes(index)
+    }
+
+    /// Mutably borrow the serialized bytes of the record at `index`,
+    /// or return `None` if it is out of bounds.
+    pub fn get_bytes_mut(
+        &mut self,
+        index: usize,
+    ) -> Option<&mut [u8; <T as SerialSize>::SIZE]> {
+        let offset = index.checked_mul(<T as SerialSize>::SIZE)?;
+        self.records.get_mut(offset..)?.first_chunk_mut()
+    }
+
+    /// Iterate over the serialized bytes of all records.
+    pub fn iter_bytes_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut [u8; <T as SerialSize>::SIZE]>
+           + ExactSizeIterator
+           + '_ {
+        self.records.array_chunks_mut()
+    }
+}
+
+impl<'a, T: Deserialize> RecordSliceMut<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Deserialize the record at `index`, or return `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<Result<T, T::Error>> {
+        self.as_record_slice().get(index)
+    }
+
+    /// Deserialize all records in order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.as_record_slice().iter()
+    }
+}
+
+impl<'a, T: Serialize> RecordSliceMut<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Serialize `value` into the record at `index`.
+    ///
+    /// # Panics
+    /// If `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: &T) {
+        let len = self.len();
+        let Some(buffer) = self.get_bytes_mut(index) else {
+            panic!("record index {index} out of bounds for {len} records");
+        };
+        value.serialize(buffer)
+    }
+}
+
+/// An iterator deserializing the records of a [`RecordSlice`] or [`RecordSliceMut`].
+#[derive(Debug)]
+pub struct Iter<'a, T: SerialSize>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    chunks: ArrayChunks<'a, u8, { <T as SerialSize>::SIZE }>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Deserialize + 'a> Iterator for Iter<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    type Item = Result<T, T::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(T::deserialize)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl<'a, T: Deserialize + 'a> DoubleEndedIterator for Iter<'a, T>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.chunks.next_back().map(T::deserialize)
+    }
+}
+
+impl<'a, T: Deserialize + 'a> ExactSizeIterator for Iter<'a, T> where
+    [(); <T as SerialSize>::SIZE]:
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordSlice, RecordSliceMut};
+    use crate::impls::core::tuple::DeserializeTuple2Error;
+    use crate::impls::IllegalBitPattern;
+    use crate::{SerialSize, Serialize};
+
+    type Record = (u16, bool);
+
+    #[test]
+    fn test_records() {
+        let mut bytes = [0; 3 * <Record as SerialSize>::SIZE + 2];
+        let mut records = RecordSliceMut::<Record>::new(&mut bytes);
+        assert_eq!(3, records.len());
+        assert_eq!(2, records.remainder().len());
+
+        records.set(0, &(1, true));
+        records.set(2, &(3, false));
+        assert_eq!(Some(Ok((1, true))), records.get(0));
+        assert_eq!(None, records.get(3));
+
+        let records = RecordSlice::<Record>::new(&bytes);
+        assert!(records
+            .iter()
+            .eq([Ok((1, true)), Ok((0, false)), Ok((3, false))]));
+        assert_eq!(Some(Ok((3, false))), records.iter().next_back());
+        assert_eq!(None, records.get(usize::MAX));
+    }
+
+    #[test]
+    fn test_invalid_record() {
+        let mut bytes = [0; 2 * <Record as SerialSize>::SIZE];
+        (7u16, true).serialize((&mut bytes[..3]).try_into().unwrap());
+        bytes[5] = 2;
+
+        let records = RecordSlice::<Record>::new(&bytes);
+        let mut iter = records.iter();
+        assert_eq!(Some(Ok((7, true))), iter.next());
+        assert_eq!(
+            Some(Err(DeserializeTuple2Error::T1(IllegalBitPattern))),
+            iter.next()
+        );
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_partial() {
+        let bytes = [1, 2, 3];
+        let records = RecordSlice::<u32>::new(&bytes);
+        assert!(records.is_empty());
+        assert_eq!(0, records.iter().len());
+        assert_eq!(&bytes, records.remainder());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_sized() {
+        RecordSlice::<()>::new(&[]);
+    }
+}
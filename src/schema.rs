@@ -0,0 +1,446 @@
+//! Machine-readable descriptions of serialized forms.
+//!
+//! Every type implementing [`Schema`] describes the layout of its serialized form
+//! as a constant [`Layout`], which is assembled from the layouts of its parts.
+//! Transparent wrappers such as `Box<T>` or `Wrapping<T>` share the layout of `T`.
+//!
+//! A layout captures the position, size and primitive kind of every value,
+//! and the tags of enums like `Option`.
+//! It does not capture constraints across or within values,
+//! such as the sub-second nanoseconds of a `Duration` or the order of an `Ordered` range.
+
+use crate::plain::NATIVE_ENDIAN;
+use crate::SerialSize;
+
+#[cfg(feature = "alloc")]
+pub mod c;
+#[cfg(feature = "alloc")]
+pub mod compat;
+#[cfg(feature = "alloc")]
+pub mod dynamic;
+#[cfg(feature = "alloc")]
+pub mod json;
+#[cfg(feature = "alloc")]
+pub mod lua;
+#[cfg(feature = "alloc")]
+pub mod python;
+
+/// A type whose serialized form is described by a [`Layout`].
+pub trait Schema: SerialSize {
+    /// The layout of the serialized form of `Self`.
+    /// Its `size` equals `<Self as SerialSize>::SIZE`.
+    const SCHEMA: Layout;
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Layout {
+    /// The name of the described type, without generic arguments.
+    pub name: &'static str,
+    /// The size of the serialized form in bytes.
+    pub size: usize,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Kind {
+    /// A primitive value that fills the whole layout.
+    Primitive {
+        primitive: Primitive,
+        endian: Endian,
+    },
+    /// `len` consecutive elements.
+    Array {
+        element: &'static Layout,
+        len: usize,
+    },
+    /// Fields at fixed offsets.
+    Struct { fields: &'static [Field] },
+    /// A tag at offset `0`, followed by the fields of the variant selected by the tag.
+    ///
+    /// Bytes that are not covered by the tag or the fields of the selected variant
+    /// are zero when serialized. If `zeroed_padding` is set,
+    /// they also have to be zero to deserialize.
+    Enum {
+        tag: &'static Layout,
+        variants: &'static [Variant],
+        zeroed_padding: bool,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Primitive {
+    /// `0` for `false` or `1` for `true`.
+    Bool,
+    /// A Unicode scalar value, stored as an unsigned integer.
+    Char,
+    Unsigned,
+    Signed,
+    /// An IEEE 754 binary floating point number.
+    Float,
+    /// A non-zero unsigned integer.
+    NonZeroUnsigned,
+    /// A non-zero signed integer.
+    NonZeroSigned,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    /// The byte order of the target.
+    pub const NATIVE: Self = if cfg!(target_endian = "big") {
+        Self::Big
+    } else {
+        Self::Little
+    };
+
+    /// The byte order of primitives, as selected by the `primitive_le` and `primitive_be` features.
+    pub const PRIMITIVE: Self = match (NATIVE_ENDIAN, Self::NATIVE) {
+        | (true, endian) => endian,
+        | (false, Self::Little) => Self::Big,
+        | (false, Self::Big) => Self::Little,
+    };
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Field {
+    pub name: &'static str,
+    /// The offset of the field from the start of the enclosing layout.
+    pub offset: usize,
+    pub layout: &'static Layout,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Variant {
+    pub name: &'static str,
+    /// The value of the tag selecting this variant.
+    pub tag: i64,
+    /// The fields of this variant, with offsets from the start of the enum.
+    pub fields: &'static [Field],
+}
+
+impl Layout {
+    /// The layout of a primitive of `size` bytes,
+    /// serialized in the byte order selected by the crate features.
+    pub const fn primitive(
+        name: &'static str,
+        size: usize,
+        primitive: Primitive,
+    ) -> Self {
+        Self::primitive_with_endian(name, size, primitive, Endian::PRIMITIVE)
+    }
+
+    pub const fn primitive_with_endian(
+        name: &'static str,
+        size: usize,
+        primitive: Primitive,
+        endian: Endian,
+    ) -> Self {
+        Self {
+            name,
+            size,
+            kind: Kind::Primitive { primitive, endian },
+        }
+    }
+
+    /// The layout of `len` consecutive `element`s.
+    pub const fn array(name: &'static str, element: &'static Layout, len: usize) -> Self {
+        Self {
+            name,
+            size: element.size * len,
+            kind: Kind::Array { element, len },
+        }
+    }
+
+    /// The layout of a struct with the given `fields`, which extends to the end of its last field.
+    /// See [`sequence`] for consecutive fields.
+    pub const fn structure(name: &'static str, fields: &'static [Field]) -> Self {
+        Self {
+            name,
+            size: end_of(fields),
+            kind: Kind::Struct { fields },
+        }
+    }
+
+    /// The layout of an enum of `size` bytes.
+    pub const fn enumeration(
+        name: &'static str,
+        size: usize,
+        tag: &'static Layout,
+        variants: &'static [Variant],
+        zeroed_padding: bool,
+    ) -> Self {
+        Self {
+            name,
+            size,
+            kind: Kind::Enum {
+                tag,
+                variants,
+                zeroed_padding,
+            },
+        }
+    }
+}
+
+impl Layout {
+    /// The field of the `Some` variant if `self` is laid out like an `Option`:
+    /// an enum with a `bool` tag, a fieldless `None` variant for `0`
+    /// and a `Some` variant with a single field for `1`.
+    pub fn option_payload(&self) -> Option<&'static Field> {
+        match self.kind {
+            | Kind::Enum {
+                tag:
+                    Layout {
+                        kind:
+                            Kind::Primitive {
+                                primitive: Primitive::Bool,
+                                ..
+                            },
+                        ..
+                    },
+                variants:
+                    [Variant {
+                        name: "None",
+                        tag: 0,
+                        fields: [],
+                    }, Variant {
+                        name: "Some",
+                        tag: 1,
+                        fields: [payload],
+                    }],
+                ..
+            } => Some(payload),
+            | _ => None,
+        }
+    }
+}
+
+/// Lay out `fields` consecutively, starting at `offset`.
+pub const fn sequence<const LEN: usize>(
+    offset: usize,
+    fields: [(&'static str, &'static Layout); LEN],
+) -> [Field; LEN] {
+    let mut result = [Field {
+        name: "",
+        offset: 0,
+        layout: &EMPTY,
+    }; LEN];
+    let mut offset = offset;
+    let mut i = 0;
+    while i < LEN {
+        let (name, layout) = fields[i];
+        result[i] = Field {
+            name,
+            offset,
+            layout,
+        };
+        offset += layout.size;
+        i += 1;
+    }
+    result
+}
+
+/// The end of the last of `fields`.
+const fn end_of(fields: &[Field]) -> usize {
+    let mut end = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        let field_end = fields[i].offset + fields[i].layout.size;
+        if field_end > end {
+            end = field_end;
+        }
+        i += 1;
+    }
+    end
+}
+
+const EMPTY: Layout = Layout::structure("()", &[]);
+
+/// The names declared by a code generator, which derives them from paths,
+/// such as `Message_a_b` for both the field `a_b` and the nested field `a.b`.
+/// A name that is already taken gets a number appended.
+#[cfg(feature = "alloc")]
+pub(crate) struct Names(alloc::collections::BTreeSet<alloc::string::String>);
+
+#[cfg(feature = "alloc")]
+impl Names {
+    pub(crate) const fn new() -> Self {
+        Self(alloc::collections::BTreeSet::new())
+    }
+
+    /// Take `name`, or the first free `name_2`, `name_3`, ... if it is taken.
+    pub(crate) fn take(&mut self, name: &str) -> alloc::string::String {
+        let mut unique = alloc::string::String::from(name);
+        let mut i = 2;
+        while !self.0.insert(unique.clone()) {
+            unique = alloc::format!("{name}_{i}");
+            i += 1;
+        }
+        unique
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Endian, Field, Kind, Layout, Primitive, Schema, Variant};
+    use crate::SerialSize;
+    use core::cmp::{Ordering, Reverse};
+    use core::net::{IpAddr, SocketAddr, SocketAddrV6};
+    use core::num::{NonZeroI32, Wrapping};
+    use core::ops::{Bound, Range, RangeFull};
+    use core::time::Duration;
+
+    /// Check that `layout` is consistent with itself:
+    /// every field and tag lies within the layout, and struct fields do not overlap.
+    pub(crate) fn assert_consistent(layout: &Layout) {
+        match layout.kind {
+            | Kind::Primitive { .. } => {}
+            | Kind::Array { element, len } => {
+                assert_eq!(element.size * len, layout.size, "{}", layout.name);
+                assert_consistent(element);
+            }
+            | Kind::Struct { fields } => assert_fields(layout, fields, 0),
+            | Kind::Enum { tag, variants, .. } => {
+                assert!(matches!(tag.kind, Kind::Primitive { .. }));
+                for variant in variants {
+                    assert_fields(layout, variant.fields, tag.size);
+                }
+            }
+        }
+    }
+
+    fn assert_fields(layout: &Layout, fields: &[Field], start: usize) {
+        let mut end = start;
+        for field in fields {
+            assert!(
+                field.offset >= end,
+                "{}.{} overlaps",
+                layout.name,
+                field.name
+            );
+            end = field.offset + field.layout.size;
+            assert!(
+                end <= layout.size,
+                "{}.{} out of bounds",
+                layout.name,
+                field.name
+            );
+            assert_consistent(field.layout);
+        }
+    }
+
+    fn assert_schema<T: Schema>() {
+        assert_eq!(
+            <T as SerialSize>::SIZE,
+            T::SCHEMA.size,
+            "{}",
+            T::SCHEMA.name
+        );
+        assert_consistent(&T::SCHEMA);
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_schema::<u8>();
+        assert_schema::<i128>();
+        assert_schema::<f32>();
+        assert_schema::<char>();
+        assert_schema::<NonZeroI32>();
+        assert_schema::<()>();
+        assert_schema::<(u8, bool, [u16; 3])>();
+        assert_schema::<Option<Option<(i8, f64)>>>();
+        assert_schema::<[Option<u32>; 0]>();
+        assert_schema::<Wrapping<Reverse<u64>>>();
+        assert_schema::<Ordering>();
+        assert_schema::<Duration>();
+        assert_schema::<Range<u16>>();
+        assert_schema::<RangeFull>();
+        assert_schema::<Bound<i32>>();
+        assert_schema::<IpAddr>();
+        assert_schema::<SocketAddr>();
+    }
+
+    #[test]
+    fn test_tuple() {
+        let Kind::Struct { fields } = <(u8, u32, bool)>::SCHEMA.kind else {
+            panic!("tuples are structs");
+        };
+        let offsets = fields.iter().map(|field| (field.name, field.offset));
+        assert!(offsets.eq([("0", 0), ("1", 1), ("2", 5)]));
+        assert_eq!(u32::SCHEMA, *fields[1].layout);
+    }
+
+    #[test]
+    fn test_option() {
+        let Kind::Enum {
+            tag,
+            variants,
+            zeroed_padding,
+        } = <Option<u16>>::SCHEMA.kind
+        else {
+            panic!("options are enums");
+        };
+        assert_eq!(bool::SCHEMA, *tag);
+        assert!(!zeroed_padding);
+        assert_eq!(
+            ("None", 0, 0),
+            (variants[0].name, variants[0].tag, variants[0].fields.len())
+        );
+        assert_eq!(("Some", 1), (variants[1].name, variants[1].tag));
+        assert_eq!(1, variants[1].fields[0].offset);
+    }
+
+    #[test]
+    fn test_option_payload() {
+        static FIELDS: [Field; 1] = [Field {
+            name: "0",
+            offset: 1,
+            layout: &u8::SCHEMA,
+        }];
+        static VARIANTS: [Variant; 2] = [
+            Variant {
+                name: "Nothing",
+                tag: 0,
+                fields: &[],
+            },
+            Variant {
+                name: "Some",
+                tag: 1,
+                fields: &FIELDS,
+            },
+        ];
+        static MAYBE: Layout =
+            Layout::enumeration("Maybe", 2, &bool::SCHEMA, &VARIANTS, false);
+
+        assert_eq!(
+            Some(&u16::SCHEMA),
+            <Option<u16>>::SCHEMA
+                .option_payload()
+                .map(|field| field.layout)
+        );
+        assert_eq!(None, MAYBE.option_payload());
+    }
+
+    #[test]
+    fn test_endian() {
+        let Kind::Struct { fields } = SocketAddrV6::SCHEMA.kind else {
+            panic!("socket addresses are structs");
+        };
+        assert_eq!("port", fields[1].name);
+        assert_eq!(
+            Kind::Primitive {
+                primitive: Primitive::Unsigned,
+                endian: Endian::Big,
+            },
+            fields[1].layout.kind
+        );
+
+        let Kind::Primitive { endian, .. } = u16::SCHEMA.kind else {
+            panic!("u16 is a primitive");
+        };
+        assert_eq!(Endian::PRIMITIVE, endian);
+    }
+}
@@ -0,0 +1,542 @@
+//! Generate C headers from [`Layout`]s.
+//!
+//! The header declares a packed struct for every struct and enum in the layout,
+//! named after the path to it, such as `Message_header` for the `header` field of `Message`.
+//! Enums are a tag followed by a union of the payloads of their variants,
+//! and their tags are declared as enum constants like `Message_kind_tag_Some`.
+//! Names that would clash, like those of the field `a_b` and the nested field `a.b`,
+//! get a number appended, such as `Message_a_b_2`.
+//!
+//! Fields hold the serialized bytes as they are.
+//! Multi-byte integers have to be converted to the host byte order with the
+//! `ARRISE_LE*` and `ARRISE_BE*` macros, according to the byte order noted next to the field.
+//! Floats are declared as `arrise_f32le` and the like, which are `float` or `double` if
+//! their byte order is the host's, and `uint32_t` or `uint64_t` otherwise.
+//! They are read and written with `arrise_get_f32le`, `arrise_set_f32le` and the like.
+//! 128-bit integers are declared as byte arrays.
+//! Zero-sized fields are omitted.
+
+use crate::schema::{Endian, Field, Kind, Layout, Names, Primitive, Variant};
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Byte order conversions, shared by all generated headers.
+const BYTE_ORDER_MACROS: &str = "\
+#ifndef ARRISE_BYTE_ORDER_MACROS
+#define ARRISE_BYTE_ORDER_MACROS
+#if __BYTE_ORDER__ == __ORDER_LITTLE_ENDIAN__
+#define ARRISE_LE16(x) (x)
+#define ARRISE_LE32(x) (x)
+#define ARRISE_LE64(x) (x)
+#define ARRISE_BE16(x) __builtin_bswap16(x)
+#define ARRISE_BE32(x) __builtin_bswap32(x)
+#define ARRISE_BE64(x) __builtin_bswap64(x)
+#else
+#define ARRISE_LE16(x) __builtin_bswap16(x)
+#define ARRISE_LE32(x) __builtin_bswap32(x)
+#define ARRISE_LE64(x) __builtin_bswap64(x)
+#define ARRISE_BE16(x) (x)
+#define ARRISE_BE32(x) (x)
+#define ARRISE_BE64(x) (x)
+#endif
+#endif
+
+#ifndef ARRISE_FLOAT_HELPERS
+#define ARRISE_FLOAT_HELPERS
+#if __BYTE_ORDER__ == __ORDER_LITTLE_ENDIAN__
+typedef float arrise_f32le;
+typedef double arrise_f64le;
+typedef uint32_t arrise_f32be;
+typedef uint64_t arrise_f64be;
+#else
+typedef uint32_t arrise_f32le;
+typedef uint64_t arrise_f64le;
+typedef float arrise_f32be;
+typedef double arrise_f64be;
+#endif
+#define ARRISE_FLOAT_HELPER(name, float_type, bits_type, swap) \
+    static inline float_type arrise_get_##name(const void *field) { \
+        bits_type bits; \
+        float_type value; \
+        memcpy(&bits, field, sizeof bits); \
+        bits = swap(bits); \
+        memcpy(&value, &bits, sizeof value); \
+        return value; \
+    } \
+    static inline void arrise_set_##name(void *field, float_type value) { \
+        bits_type bits; \
+        memcpy(&bits, &value, sizeof bits); \
+        bits = swap(bits); \
+        memcpy(field, &bits, sizeof bits); \
+    }
+ARRISE_FLOAT_HELPER(f32le, float, uint32_t, ARRISE_LE32)
+ARRISE_FLOAT_HELPER(f64le, double, uint64_t, ARRISE_LE64)
+ARRISE_FLOAT_HELPER(f32be, float, uint32_t, ARRISE_BE32)
+ARRISE_FLOAT_HELPER(f64be, double, uint64_t, ARRISE_BE64)
+#undef ARRISE_FLOAT_HELPER
+#endif
+";
+
+/// Generate a C header declaring the type `name` with the given `layout`.
+///
+/// `name` must be a valid C identifier.
+pub fn header(name: &str, layout: &Layout) -> String {
+    let mut generator = Generator {
+        definitions: String::new(),
+        asserts: String::new(),
+        names: Names::new(),
+    };
+    let composite = matches!(layout.kind, Kind::Struct { .. } | Kind::Enum { .. });
+    if !composite {
+        generator.names.take(name);
+    }
+    let (ty, suffix) = generator.declare(name, layout);
+    if !composite {
+        writeln!(generator.definitions, "typedef {ty} {name}{suffix};\n").unwrap();
+        assert_size(&mut generator.asserts, name, layout.size);
+    }
+
+    let guard = format!("ARRISE_{}_H", name.to_ascii_uppercase());
+    format!(
+        "/* Generated by arrise from the layout of `{}`. Do not edit. */\n\
+         #ifndef {guard}\n\
+         #define {guard}\n\
+         \n\
+         #include <stddef.h>\n\
+         #include <stdint.h>\n\
+         #include <string.h>\n\
+         \n\
+         {BYTE_ORDER_MACROS}\n\
+         {}\
+         {}\
+         \n\
+         #endif /* {guard} */\n",
+        layout.name, generator.definitions, generator.asserts
+    )
+}
+
+struct Generator {
+    definitions: String,
+    asserts: String,
+    names: Names,
+}
+
+impl Generator {
+    /// Define the types required by `layout` under `name`.
+    /// Returns the C type and array suffix of a declaration of `layout`.
+    fn declare(&mut self, name: &str, layout: &Layout) -> (String, String) {
+        match layout.kind {
+            | Kind::Primitive { primitive, endian } => {
+                primitive_type(primitive, endian, layout.size)
+            }
+            | Kind::Array { element, len } => {
+                let (ty, suffix) = self.declare(&format!("{name}_element"), element);
+                (ty, format!("[{len}]{suffix}"))
+            }
+            | Kind::Struct { fields } => (
+                self.define_struct(name, fields, 0, layout.size),
+                String::new(),
+            ),
+            | Kind::Enum { tag, variants, .. } => {
+                (self.define_enum(name, layout, tag, variants), String::new())
+            }
+        }
+    }
+
+    /// Define a packed struct `name` of `size` bytes holding `fields`,
+    /// whose offsets are relative to `base`.
+    /// The struct is padded to `size` bytes.
+    /// Returns the name of the struct, which is made unique.
+    fn define_struct(
+        &mut self,
+        name: &str,
+        fields: &[Field],
+        base: usize,
+        size: usize,
+    ) -> String {
+        let name = self.names.take(name);
+        let mut body = String::new();
+        let mut offsets = String::new();
+        let mut end = base;
+        for field in fields.iter().filter(|field| field.layout.size != 0) {
+            let member = identifier(field.name);
+            pad(&mut body, field.offset - end, end - base);
+            let (ty, suffix) =
+                self.declare(&format!("{name}_{}", field.name), field.layout);
+            write!(body, "    {ty} {member}{suffix};").unwrap();
+            if let Some(endian) = byte_order(field.layout) {
+                write!(body, " /* {endian} */").unwrap();
+            }
+            body.push('\n');
+            writeln!(
+                offsets,
+                "_Static_assert(offsetof({name}, {member}) == {}, \"offset of {name}.{member}\");",
+                field.offset - base
+            )
+            .unwrap();
+            end = field.offset + field.layout.size;
+        }
+        pad(&mut body, base + size - end, end - base);
+
+        writeln!(
+            self.definitions,
+            "typedef struct __attribute__((packed)) {name} {{\n{body}}} {name};\n"
+        )
+        .unwrap();
+        self.asserts.push_str(&offsets);
+        assert_size(&mut self.asserts, &name, size);
+        name
+    }
+
+    /// Define the tag constants and a packed struct `name` for an enum.
+    /// Returns the name of the struct, which is made unique.
+    fn define_enum(
+        &mut self,
+        name: &str,
+        layout: &Layout,
+        tag: &Layout,
+        variants: &[Variant],
+    ) -> String {
+        let name = self.names.take(name);
+        let mut constants = String::new();
+        for variant in variants {
+            let constant = self
+                .names
+                .take(&format!("{name}_tag_{}", identifier(variant.name)));
+            writeln!(constants, "    {constant} = {},", variant.tag).unwrap();
+        }
+        writeln!(self.definitions, "enum {{\n{constants}}};\n").unwrap();
+
+        let payload_size = layout.size - tag.size;
+        let mut payload = String::new();
+        for variant in variants {
+            if variant.fields.iter().all(|field| field.layout.size == 0) {
+                continue;
+            }
+            let member = identifier(variant.name);
+            let variant_name = self.define_struct(
+                &format!("{name}_{}", variant.name),
+                variant.fields,
+                tag.size,
+                payload_size,
+            );
+            writeln!(payload, "        {variant_name} {member};").unwrap();
+        }
+
+        let (tag_type, _) = match tag.kind {
+            | Kind::Primitive { primitive, endian } => {
+                primitive_type(primitive, endian, tag.size)
+            }
+            | _ => primitive_type(Primitive::Unsigned, Endian::PRIMITIVE, tag.size),
+        };
+        let mut body = format!("    {tag_type} tag;");
+        if let Some(endian) = byte_order(tag) {
+            write!(body, " /* {endian} */").unwrap();
+        }
+        body.push('\n');
+        if payload_size != 0 {
+            write!(
+                body,
+                "    union __attribute__((packed)) {{\n{payload}        \
+                 uint8_t bytes[{payload_size}];\n    }} payload;\n"
+            )
+            .unwrap();
+            writeln!(
+                self.asserts,
+                "_Static_assert(offsetof({name}, payload) == {}, \"offset of {name}.payload\");",
+                tag.size
+            )
+            .unwrap();
+        }
+        writeln!(
+            self.definitions,
+            "typedef struct __attribute__((packed)) {name} {{\n{body}}} {name};\n"
+        )
+        .unwrap();
+        assert_size(&mut self.asserts, &name, layout.size);
+        name
+    }
+}
+
+fn assert_size(asserts: &mut String, name: &str, size: usize) {
+    writeln!(
+        asserts,
+        "_Static_assert(sizeof({name}) == {size}, \"size of {name}\");"
+    )
+    .unwrap();
+}
+
+/// Append `len` bytes of padding at `offset` to a struct body.
+fn pad(body: &mut String, len: usize, offset: usize) {
+    if len != 0 {
+        writeln!(body, "    uint8_t _pad{offset}[{len}];").unwrap();
+    }
+}
+
+fn primitive_type(primitive: Primitive, endian: Endian, size: usize) -> (String, String) {
+    let ty = match (primitive, endian, size) {
+        | (Primitive::Float, Endian::Little, 4) => "arrise_f32le",
+        | (Primitive::Float, Endian::Little, 8) => "arrise_f64le",
+        | (Primitive::Float, Endian::Big, 4) => "arrise_f32be",
+        | (Primitive::Float, Endian::Big, 8) => "arrise_f64be",
+        | (Primitive::Signed | Primitive::NonZeroSigned, _, 1) => "int8_t",
+        | (Primitive::Signed | Primitive::NonZeroSigned, _, 2) => "int16_t",
+        | (Primitive::Signed | Primitive::NonZeroSigned, _, 4) => "int32_t",
+        | (Primitive::Signed | Primitive::NonZeroSigned, _, 8) => "int64_t",
+        | (_, _, 1) => "uint8_t",
+        | (_, _, 2) => "uint16_t",
+        | (_, _, 4) => "uint32_t",
+        | (_, _, 8) => "uint64_t",
+        | (_, _, size) => return (String::from("uint8_t"), format!("[{size}]")),
+    };
+    (String::from(ty), String::new())
+}
+
+/// The byte order of a multi-byte primitive, if `layout` is one.
+fn byte_order(layout: &Layout) -> Option<&'static str> {
+    match layout.kind {
+        | Kind::Primitive { .. } if layout.size == 1 => None,
+        | Kind::Primitive { endian, .. } => Some(match endian {
+            | Endian::Little => "little-endian",
+            | Endian::Big => "big-endian",
+        }),
+        | _ => None,
+    }
+}
+
+/// Turn a field or variant name into a C identifier, prefixing names like `0` with `_`.
+fn identifier(name: &str) -> String {
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        | true => format!("_{name}"),
+        | false => String::from(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::header;
+    use crate::schema::{Endian, Schema};
+    use alloc::format;
+    use core::net::SocketAddrV4;
+
+    #[test]
+    fn test_header() {
+        let header = header("Message", &<(u8, Option<i32>, [u16; 2])>::SCHEMA);
+
+        assert!(header.contains(
+            "typedef struct __attribute__((packed)) Message_1_Some {\n    int32_t _0;"
+        ));
+        assert!(header.contains("    Message_1_tag_Some = 1,\n"));
+        assert!(header.contains("    Message_1 _1;\n"));
+        assert!(header.contains("    uint16_t _2[2];"));
+        assert!(header.contains("_Static_assert(sizeof(Message) == 10, "));
+        assert!(header.contains("_Static_assert(offsetof(Message, _2) == 6, "));
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        let header = header("Port", &u16::SCHEMA);
+        assert!(header.contains("typedef uint16_t Port;"));
+        assert!(header.contains("_Static_assert(sizeof(Port) == 2, "));
+    }
+
+    #[test]
+    fn test_byte_order() {
+        let header = header("Addr", &SocketAddrV4::SCHEMA);
+        assert!(header.contains("    uint8_t ip[4];\n"));
+        assert!(header.contains("    uint16_t port; /* big-endian */\n"));
+    }
+
+    #[test]
+    fn test_floats() {
+        let header = header("Sample", &<(f32, f64)>::SCHEMA);
+        let (f32_type, f64_type) = match Endian::PRIMITIVE {
+            | Endian::Little => ("arrise_f32le", "arrise_f64le"),
+            | Endian::Big => ("arrise_f32be", "arrise_f64be"),
+        };
+        assert!(header.contains(&format!("    {f32_type} _0; /* ")));
+        assert!(header.contains(&format!("    {f64_type} _1; /* ")));
+        assert!(header.contains("typedef uint32_t arrise_f32be;\n"));
+    }
+
+    /// Run the system C compiler in `dir` with `args`, returning whether it succeeded.
+    #[cfg(feature = "std")]
+    fn cc(dir: &std::path::Path, args: &[&str]) -> bool {
+        std::process::Command::new("cc")
+            .args(["-std=c11", "-Wall", "-Werror"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .expect("failed to run `cc`")
+            .success()
+    }
+
+    /// Compile a header with the system C compiler, which checks the `_Static_assert`s.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `cc`"]
+    fn test_compile() {
+        use crate::impls::core::range::Ordered;
+        use core::net::IpAddr;
+        use core::ops::{Bound, Range};
+        use core::time::Duration;
+
+        type Message = (
+            bool,
+            Option<(char, [Option<u8>; 3])>,
+            Bound<i64>,
+            IpAddr,
+            Ordered<Range<u128>>,
+            Duration,
+            (f32, [f64; 2]),
+            (),
+        );
+
+        let dir = std::env::temp_dir().join(format!("arrise-c-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("message.h"), header("Message", &Message::SCHEMA))
+            .unwrap();
+        let success = cc(&dir, &["-fsyntax-only", "-x", "c", "message.h"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(success);
+    }
+
+    /// Compile a header whose paths only differ in `.` and `_`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `cc`"]
+    fn test_names() {
+        use crate::schema::{Field, Layout};
+
+        static A_B: Layout = Layout::structure(
+            "A_B",
+            &[Field {
+                name: "x",
+                offset: 0,
+                layout: &u8::SCHEMA,
+            }],
+        );
+        static B: Layout = Layout::structure(
+            "B",
+            &[Field {
+                name: "y",
+                offset: 0,
+                layout: &u8::SCHEMA,
+            }],
+        );
+        static A: Layout = Layout::structure(
+            "A",
+            &[Field {
+                name: "b",
+                offset: 0,
+                layout: &B,
+            }],
+        );
+        static MESSAGE: Layout = Layout::structure(
+            "Message",
+            &[
+                Field {
+                    name: "a_b",
+                    offset: 0,
+                    layout: &A_B,
+                },
+                Field {
+                    name: "a",
+                    offset: 1,
+                    layout: &A,
+                },
+                Field {
+                    name: "c_tag_Some",
+                    offset: 2,
+                    layout: &A_B,
+                },
+                Field {
+                    name: "c",
+                    offset: 3,
+                    layout: &<Option<u8>>::SCHEMA,
+                },
+            ],
+        );
+
+        let header = header("Message", &MESSAGE);
+        assert!(header.contains("    Message_a_b a_b;\n"));
+        assert!(header.contains(
+            "typedef struct __attribute__((packed)) Message_a {\n    Message_a_b_2 b;\n"
+        ));
+        assert!(header.contains("    Message_c_tag_Some_2 = 1,\n"));
+
+        let dir =
+            std::env::temp_dir().join(format!("arrise-c-names-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("message.h"), header).unwrap();
+        let success = cc(&dir, &["-fsyntax-only", "-x", "c", "message.h"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(success);
+    }
+
+    /// Read and write floats of both byte orders with the generated helpers,
+    /// whichever byte order the host has.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `cc`"]
+    fn test_float_helpers() {
+        use crate::schema::{Field, Layout, Primitive};
+        use std::process::Command;
+
+        static LE: Layout =
+            Layout::primitive_with_endian("f32", 4, Primitive::Float, Endian::Little);
+        static BE: Layout =
+            Layout::primitive_with_endian("f64", 8, Primitive::Float, Endian::Big);
+        static FLOATS: Layout = Layout::structure(
+            "Floats",
+            &[
+                Field {
+                    name: "le",
+                    offset: 0,
+                    layout: &LE,
+                },
+                Field {
+                    name: "be",
+                    offset: 4,
+                    layout: &BE,
+                },
+            ],
+        );
+
+        let bytes = |le: f32, be: f64| -> String {
+            let bytes: Vec<String> = (le.to_le_bytes().iter())
+                .chain(&be.to_be_bytes())
+                .map(|byte| format!("{byte:#04x}"))
+                .collect();
+            bytes.join(", ")
+        };
+        let program = format!(
+            "#include \"floats.h\"\n\
+             static const unsigned char read[] = {{{}}};\n\
+             static const unsigned char written[] = {{{}}};\n\
+             int main(void) {{\n\
+                 Floats floats;\n\
+                 memcpy(&floats, read, sizeof floats);\n\
+                 if (arrise_get_f32le(&floats.le) != 1.5f) return 1;\n\
+                 if (arrise_get_f64be(&floats.be) != -2.25) return 2;\n\
+                 arrise_set_f32le(&floats.le, -0.5f);\n\
+                 arrise_set_f64be(&floats.be, 1e100);\n\
+                 return memcmp(&floats, written, sizeof floats) != 0 ? 3 : 0;\n\
+             }}\n",
+            bytes(1.5, -2.25),
+            bytes(-0.5, 1e100),
+        );
+
+        let dir =
+            std::env::temp_dir().join(format!("arrise-c-floats-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("floats.h"), header("Floats", &FLOATS)).unwrap();
+        std::fs::write(dir.join("floats.c"), program).unwrap();
+        let status = cc(&dir, &["-o", "floats", "floats.c"])
+            .then(|| Command::new(dir.join("floats")).status());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let status = status.expect("compilation failed");
+        assert_eq!(Some(0), status.unwrap().code());
+    }
+}
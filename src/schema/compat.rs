@@ -0,0 +1,504 @@
+//! Compare the layouts of two versions of a type.
+//!
+//! [`compare`] lists how the serialized form changed between an old and a new [`Layout`],
+//! such as a layout snapshot written by [`layout_to_json`](crate::schema::json::layout_to_json)
+//! and committed with a release, and the current `SCHEMA` of the type:
+//!
+//! ```ignore
+//! let snapshot = layout_from_json(include_str!("telemetry.json")).unwrap();
+//! let changes = compare(snapshot, &Telemetry::SCHEMA);
+//! assert!(changes.iter().all(|change| !change.breaking), "{changes:#?}");
+//! ```
+//!
+//! A change is breaking unless every buffer serialized with the old layout
+//! still deserializes to the same values with the new one.
+//! For example, a field added in the padding of an enum variant is compatible
+//! if the zero bytes the old layout wrote there are a valid value of the field.
+
+use crate::schema::{Field, Kind, Layout, Primitive, Variant};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The path to the changed value, like the paths of
+    /// [`JsonError::Unexpected`](crate::schema::json::JsonError::Unexpected),
+    /// with `[*]` for the elements of arrays and `.<tag>` for the tags of enums.
+    /// Removed values have their path in the old layout, all others in the new one.
+    pub path: String,
+    pub kind: ChangeKind,
+    /// Whether buffers serialized with the old layout may be rejected
+    /// or read differently with the new one.
+    pub breaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    /// The size of the whole layout, i.e. `SIZE`, changed.
+    Size { old: usize, new: usize },
+    /// A field of `size` bytes was added at `offset`.
+    Added { offset: usize, size: usize },
+    /// A field of `size` bytes at `offset` was removed.
+    Removed { offset: usize, size: usize },
+    /// A field was renamed from `old`, keeping its offset and layout.
+    Renamed { old: &'static str },
+    /// The size of a value changed.
+    Resized { old: usize, new: usize },
+    /// The offset of a field changed.
+    Moved { old: usize, new: usize },
+    /// The primitive, byte order or kind of a value changed.
+    Retyped { old: Layout, new: Layout },
+    /// A variant with `tag` was added.
+    VariantAdded { tag: i64 },
+    /// A variant with `tag` was removed.
+    VariantRemoved { tag: i64 },
+    /// The tag selecting a variant changed.
+    TagChanged { old: i64, new: i64 },
+    /// Whether padding has to be zero changed.
+    ZeroedPadding { old: bool, new: bool },
+}
+
+/// List the changes from the `old` to the `new` layout, outermost first.
+pub fn compare(old: &Layout, new: &Layout) -> Vec<Change> {
+    let mut comparison = Comparison {
+        changes: Vec::new(),
+    };
+    if old.size != new.size {
+        comparison.push(
+            "$",
+            ChangeKind::Size {
+                old: old.size,
+                new: new.size,
+            },
+            true,
+        );
+    }
+    comparison.compare(old, new, "$");
+    comparison.changes
+}
+
+struct Comparison {
+    changes: Vec<Change>,
+}
+
+impl Comparison {
+    fn push(&mut self, path: &str, kind: ChangeKind, breaking: bool) {
+        self.changes.push(Change {
+            path: String::from(path),
+            kind,
+            breaking,
+        });
+    }
+
+    /// Compare `old` and `new` at `path`, apart from their sizes.
+    fn compare(&mut self, old: &Layout, new: &Layout, path: &str) {
+        match (old.kind, new.kind) {
+            | (Kind::Primitive { .. }, Kind::Primitive { .. }) if old.kind != new.kind => {
+                self.retyped(old, new, path)
+            }
+            | (Kind::Primitive { .. }, Kind::Primitive { .. }) => {}
+            | (
+                Kind::Array {
+                    element: old_element,
+                    ..
+                },
+                Kind::Array {
+                    element: new_element,
+                    ..
+                },
+            ) => self.compare_nested(old_element, new_element, &format!("{path}[*]")),
+            | (
+                Kind::Struct { fields: old_fields },
+                Kind::Struct { fields: new_fields },
+            ) => self.compare_fields(old_fields, new_fields, (0, old.size), path, false),
+            | (
+                Kind::Enum {
+                    tag: old_tag,
+                    variants: old_variants,
+                    zeroed_padding: old_zeroed,
+                },
+                Kind::Enum {
+                    tag: new_tag,
+                    variants: new_variants,
+                    zeroed_padding: new_zeroed,
+                },
+            ) => {
+                self.compare_nested(old_tag, new_tag, &format!("{path}.<tag>"));
+                if old_zeroed != new_zeroed {
+                    let kind = ChangeKind::ZeroedPadding {
+                        old: old_zeroed,
+                        new: new_zeroed,
+                    };
+                    self.push(path, kind, new_zeroed);
+                }
+                // bytes past the old enum were never written for any variant
+                let old_extent = (old_tag.size, old.size);
+                self.compare_variants(old_variants, new_variants, old_extent, path);
+            }
+            | _ => self.retyped(old, new, path),
+        }
+    }
+
+    /// Compare `old` and `new` at `path`, including their sizes.
+    fn compare_nested(&mut self, old: &Layout, new: &Layout, path: &str) {
+        if old.size != new.size {
+            let kind = ChangeKind::Resized {
+                old: old.size,
+                new: new.size,
+            };
+            self.push(path, kind, true);
+        }
+        self.compare(old, new, path);
+    }
+
+    fn retyped(&mut self, old: &Layout, new: &Layout, path: &str) {
+        // dropping the non-zero requirement accepts every old value as it was
+        let breaking = !matches!(
+            (old.kind, new.kind),
+            (
+                Kind::Primitive {
+                    primitive: Primitive::NonZeroUnsigned,
+                    endian: old_endian,
+                },
+                Kind::Primitive {
+                    primitive: Primitive::Unsigned,
+                    endian: new_endian,
+                },
+            ) | (
+                Kind::Primitive {
+                    primitive: Primitive::NonZeroSigned,
+                    endian: old_endian,
+                },
+                Kind::Primitive {
+                    primitive: Primitive::Signed,
+                    endian: new_endian,
+                },
+            ) if old_endian == new_endian && old.size == new.size
+        );
+        self.push(
+            path,
+            ChangeKind::Retyped {
+                old: *old,
+                new: *new,
+            },
+            breaking,
+        );
+    }
+
+    fn compare_variants(
+        &mut self,
+        old_variants: &[Variant],
+        new_variants: &[Variant],
+        old_extent: (usize, usize),
+        path: &str,
+    ) {
+        for old_variant in old_variants {
+            let variant_path = format!("{path}.{}", old_variant.name);
+            match new_variants.iter().find(|new| new.name == old_variant.name) {
+                | Some(new_variant) => {
+                    if old_variant.tag != new_variant.tag {
+                        let kind = ChangeKind::TagChanged {
+                            old: old_variant.tag,
+                            new: new_variant.tag,
+                        };
+                        self.push(&variant_path, kind, true);
+                    }
+                    self.compare_fields(
+                        old_variant.fields,
+                        new_variant.fields,
+                        old_extent,
+                        &variant_path,
+                        true,
+                    );
+                }
+                | None => {
+                    let kind = ChangeKind::VariantRemoved {
+                        tag: old_variant.tag,
+                    };
+                    self.push(&variant_path, kind, true);
+                }
+            }
+        }
+        for new_variant in new_variants {
+            if !old_variants.iter().any(|old| old.name == new_variant.name) {
+                let kind = ChangeKind::VariantAdded {
+                    tag: new_variant.tag,
+                };
+                self.push(&format!("{path}.{}", new_variant.name), kind, false);
+            }
+        }
+    }
+
+    /// Compare the fields of a struct or variant, matching them by name.
+    /// `old_extent` are the bytes the old fields could have been written to.
+    fn compare_fields(
+        &mut self,
+        old_fields: &[Field],
+        new_fields: &[Field],
+        old_extent: (usize, usize),
+        path: &str,
+        variant: bool,
+    ) {
+        let matched = |fields: &[Field], field: &Field| {
+            fields.iter().any(|other| other.name == field.name)
+        };
+        let mut removed: Vec<&Field> = old_fields
+            .iter()
+            .filter(|old| !matched(new_fields, old))
+            .collect();
+
+        for new in new_fields {
+            let new_path = field_path(path, new_fields, new, variant);
+            if let Some(old) = old_fields.iter().find(|old| old.name == new.name) {
+                if old.offset != new.offset {
+                    let kind = ChangeKind::Moved {
+                        old: old.offset,
+                        new: new.offset,
+                    };
+                    self.push(&new_path, kind, true);
+                }
+                self.compare_nested(old.layout, new.layout, &new_path);
+                continue;
+            }
+
+            let renamed = removed
+                .iter()
+                .position(|old| old.offset == new.offset && old.layout == new.layout);
+            if let Some(i) = renamed {
+                let old = removed.remove(i);
+                self.push(&new_path, ChangeKind::Renamed { old: old.name }, false);
+                continue;
+            }
+
+            let (start, end) = (new.offset, new.offset + new.layout.size);
+            // the old layout wrote zeroes to bytes not covered by its fields
+            let in_padding = old_extent.0 <= start
+                && end <= old_extent.1
+                && old_fields.iter().all(|old| {
+                    end <= old.offset || old.offset + old.layout.size <= start
+                });
+            let kind = ChangeKind::Added {
+                offset: new.offset,
+                size: new.layout.size,
+            };
+            let compatible =
+                new.layout.size == 0 || in_padding && zero_is_valid(new.layout);
+            self.push(&new_path, kind, !compatible);
+        }
+
+        for old in removed {
+            let kind = ChangeKind::Removed {
+                offset: old.offset,
+                size: old.layout.size,
+            };
+            let path = field_path(path, old_fields, old, variant);
+            self.push(&path, kind, old.layout.size != 0);
+        }
+    }
+}
+
+/// The path to `field` of `fields`, which belong to a variant if `variant` is set.
+fn field_path(path: &str, fields: &[Field], field: &Field, variant: bool) -> String {
+    match field.name.parse::<usize>() {
+        // a single positional field is written as the variant itself
+        | Ok(0) if variant && fields.len() == 1 => String::from(path),
+        | Ok(i) => format!("{path}[{i}]"),
+        | Err(_) => format!("{path}.{}", field.name),
+    }
+}
+
+/// Whether all zero bytes are a valid value of `layout`.
+fn zero_is_valid(layout: &Layout) -> bool {
+    match layout.kind {
+        | Kind::Primitive { primitive, .. } => !matches!(
+            primitive,
+            Primitive::NonZeroUnsigned | Primitive::NonZeroSigned
+        ),
+        | Kind::Array { element, len } => len == 0 || zero_is_valid(element),
+        | Kind::Struct { fields } => {
+            fields.iter().all(|field| zero_is_valid(field.layout))
+        }
+        | Kind::Enum { variants, .. } => variants.iter().any(|variant| {
+            variant.tag == 0
+                && variant
+                    .fields
+                    .iter()
+                    .all(|field| zero_is_valid(field.layout))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, Change, ChangeKind};
+    use crate::schema::json::{layout_from_json, layout_to_json};
+    use crate::schema::{sequence, Field, Layout, Schema, Variant};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::num::NonZeroU16;
+    use core::time::Duration;
+
+    fn change(path: &str, kind: ChangeKind, breaking: bool) -> Change {
+        Change {
+            path: String::from(path),
+            kind,
+            breaking,
+        }
+    }
+
+    #[test]
+    fn test_unchanged() {
+        type Message = (u32, Option<[u8; 3]>, Duration);
+        assert_eq!(
+            Vec::<Change>::new(),
+            compare(&Message::SCHEMA, &Message::SCHEMA)
+        );
+
+        let snapshot = layout_from_json(&layout_to_json(&Message::SCHEMA)).unwrap();
+        assert_eq!(Vec::<Change>::new(), compare(snapshot, &Message::SCHEMA));
+    }
+
+    #[test]
+    fn test_struct() {
+        let old = <(u8, u16, NonZeroU16)>::SCHEMA;
+        let new = <(u8, u32, u16)>::SCHEMA;
+        assert_eq!(
+            [
+                change("$", ChangeKind::Size { old: 5, new: 7 }, true),
+                change("$[1]", ChangeKind::Resized { old: 2, new: 4 }, true),
+                change("$[2]", ChangeKind::Moved { old: 3, new: 5 }, true),
+                change(
+                    "$[2]",
+                    ChangeKind::Retyped {
+                        old: NonZeroU16::SCHEMA,
+                        new: u16::SCHEMA,
+                    },
+                    false
+                ),
+            ],
+            &compare(&old, &new)[..]
+        );
+
+        const NAMED: Layout = Layout::structure(
+            "Named",
+            &sequence(0, [("id", &u32::SCHEMA), ("len", &u8::SCHEMA)]),
+        );
+        const RENAMED: Layout = Layout::structure(
+            "Named",
+            &sequence(0, [("key", &u32::SCHEMA), ("flags", &u16::SCHEMA)]),
+        );
+        assert_eq!(
+            [
+                change("$", ChangeKind::Size { old: 5, new: 6 }, true),
+                change("$.key", ChangeKind::Renamed { old: "id" }, false),
+                change("$.flags", ChangeKind::Added { offset: 4, size: 2 }, true),
+                change("$.len", ChangeKind::Removed { offset: 4, size: 1 }, true),
+            ],
+            &compare(&NAMED, &RENAMED)[..]
+        );
+    }
+
+    #[test]
+    fn test_enum() {
+        const PAYLOAD: [Field; 1] = sequence(1, [("0", &u16::SCHEMA)]);
+        const EXTENDED: [Field; 2] =
+            sequence(1, [("0", &u16::SCHEMA), ("1", &u8::SCHEMA)]);
+        const NONZERO: [Field; 2] =
+            sequence(1, [("0", &u16::SCHEMA), ("1", &NonZeroU16::SCHEMA)]);
+        const fn message(variants: &'static [Variant], zeroed_padding: bool) -> Layout {
+            Layout::enumeration("Message", 5, &u8::SCHEMA, variants, zeroed_padding)
+        }
+        const OLD: Layout = message(
+            &[
+                Variant {
+                    name: "Empty",
+                    tag: 0,
+                    fields: &[],
+                },
+                Variant {
+                    name: "Short",
+                    tag: 1,
+                    fields: &PAYLOAD,
+                },
+            ],
+            false,
+        );
+        const NEW: Layout = message(
+            &[
+                Variant {
+                    name: "Short",
+                    tag: 2,
+                    fields: &EXTENDED,
+                },
+                Variant {
+                    name: "Long",
+                    tag: 3,
+                    fields: &NONZERO,
+                },
+            ],
+            true,
+        );
+        assert_eq!(
+            [
+                change(
+                    "$",
+                    ChangeKind::ZeroedPadding {
+                        old: false,
+                        new: true
+                    },
+                    true
+                ),
+                change("$.Empty", ChangeKind::VariantRemoved { tag: 0 }, true),
+                change("$.Short", ChangeKind::TagChanged { old: 1, new: 2 }, true),
+                change(
+                    "$.Short[1]",
+                    ChangeKind::Added { offset: 3, size: 1 },
+                    false
+                ),
+                change("$.Long", ChangeKind::VariantAdded { tag: 3 }, false),
+            ],
+            &compare(&OLD, &NEW)[..]
+        );
+
+        // a zeroed field is not a valid `NonZeroU16`
+        const LONG: Layout = message(
+            &[Variant {
+                name: "Long",
+                tag: 3,
+                fields: &PAYLOAD,
+            }],
+            true,
+        );
+        assert_eq!(
+            change("$.Long[1]", ChangeKind::Added { offset: 3, size: 2 }, true),
+            compare(&LONG, &NEW)[0]
+        );
+    }
+
+    #[test]
+    fn test_nested() {
+        let old = <Option<[(u8, u8); 2]>>::SCHEMA;
+        let new = <Option<[(u8, i8); 2]>>::SCHEMA;
+        assert_eq!(
+            [change(
+                "$.Some[*][1]",
+                ChangeKind::Retyped {
+                    old: u8::SCHEMA,
+                    new: i8::SCHEMA,
+                },
+                true
+            )],
+            &compare(&old, &new)[..]
+        );
+
+        let new = <Option<[(u8, u8); 3]>>::SCHEMA;
+        assert_eq!(
+            [
+                change("$", ChangeKind::Size { old: 5, new: 7 }, true),
+                change("$.Some", ChangeKind::Resized { old: 4, new: 6 }, true),
+            ],
+            &compare(&old, &new)[..]
+        );
+    }
+}
@@ -0,0 +1,598 @@
+//! Decode and encode serialized forms at runtime, guided by a [`Layout`].
+//!
+//! [`decode_dynamic`] turns a buffer into a [`Value`] tree without the described type at hand,
+//! and [`encode_dynamic`] turns such a tree back into the bytes
+//! [`Serialize`](crate::Serialize) would have written.
+//!
+//! Decoding rejects the same bit patterns as [`Deserialize`](crate::Deserialize),
+//! as far as they are captured by the layout.
+//! Constraints the layout does not capture, such as the sub-second nanoseconds of a `Duration`,
+//! are not checked.
+
+use crate::schema::{Endian, Field, Kind, Layout, Primitive, Variant};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// A value decoded from its serialized form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Unsigned(u128),
+    Signed(i128),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Char(char),
+    /// An `Option`-like enum, see [`Layout::option_payload`].
+    Option(Option<Box<Value>>),
+    /// A struct with the positional fields `0`, `1`, …, such as a tuple.
+    Tuple(Vec<Value>),
+    Array(Vec<Value>),
+    /// A struct with named fields.
+    Struct(Vec<(&'static str, Value)>),
+    Enum {
+        variant: &'static str,
+        fields: Vec<(&'static str, Value)>,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DecodeDynamicError {
+    /// The buffer is not as long as the layout.
+    Length,
+    /// An illegal `bool`, a zero non-zero integer or non-zero padding at `offset`.
+    IllegalBitPattern { offset: usize },
+    /// An integer that is not a Unicode scalar value at `offset`.
+    CharTryFromError { offset: usize },
+    /// A tag at `offset` that selects no variant.
+    IllegalTag { offset: usize },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EncodeDynamicError {
+    /// The buffer is not as long as the layout.
+    Length,
+    /// The value at `offset` does not have the shape of the layout,
+    /// such as a struct where an array is expected or an unknown variant.
+    Mismatch { offset: usize },
+    /// The integer at `offset` does not fit its layout, or is zero for a non-zero integer.
+    OutOfRange { offset: usize },
+}
+
+/// Decode `buffer` as laid out by `layout`.
+pub fn decode_dynamic(
+    layout: &Layout,
+    buffer: &[u8],
+) -> Result<Value, DecodeDynamicError> {
+    if buffer.len() != layout.size {
+        return Err(DecodeDynamicError::Length);
+    }
+    decode(layout, buffer, 0)
+}
+
+/// Encode `value` into `buffer` as laid out by `layout`.
+/// Bytes not covered by `value`, such as the padding of enums, are zeroed.
+pub fn encode_dynamic(
+    layout: &Layout,
+    value: &Value,
+    buffer: &mut [u8],
+) -> Result<(), EncodeDynamicError> {
+    if buffer.len() != layout.size {
+        return Err(EncodeDynamicError::Length);
+    }
+    buffer.fill(0);
+    encode(layout, value, buffer, 0)
+}
+
+/// Decode `buffer`, which is exactly `layout.size` bytes long and starts at `offset`.
+fn decode(
+    layout: &Layout,
+    buffer: &[u8],
+    offset: usize,
+) -> Result<Value, DecodeDynamicError> {
+    match layout.kind {
+        | Kind::Primitive { primitive, endian } => {
+            decode_primitive(primitive, endian, buffer, offset)
+        }
+        | Kind::Array { element, len } => (0..len)
+            .map(|i| {
+                let start = i * element.size;
+                decode(
+                    element,
+                    &buffer[start..start + element.size],
+                    offset + start,
+                )
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        | Kind::Struct { fields } => {
+            let mut values = decode_fields(fields, buffer, offset)?;
+            Ok(match is_tuple(fields) {
+                | true => {
+                    Value::Tuple(values.drain(..).map(|(_, value)| value).collect())
+                }
+                | false => Value::Struct(values),
+            })
+        }
+        | Kind::Enum {
+            tag,
+            variants,
+            zeroed_padding,
+        } => {
+            let variant = variant_of(tag, variants, buffer)
+                .ok_or(DecodeDynamicError::IllegalTag { offset })?;
+            let mut values = decode_fields(variant.fields, buffer, offset)?;
+            if zeroed_padding {
+                let covered = |i: usize| {
+                    i < tag.size
+                        || variant.fields.iter().any(|field| {
+                            (field.offset..field.offset + field.layout.size).contains(&i)
+                        })
+                };
+                if let Some(i) =
+                    (0..buffer.len()).find(|&i| buffer[i] != 0 && !covered(i))
+                {
+                    return Err(DecodeDynamicError::IllegalBitPattern {
+                        offset: offset + i,
+                    });
+                }
+            }
+            Ok(match layout.option_payload() {
+                | Some(_) => {
+                    Value::Option(values.pop().map(|(_, value)| Box::new(value)))
+                }
+                | None => Value::Enum {
+                    variant: variant.name,
+                    fields: values,
+                },
+            })
+        }
+    }
+}
+
+fn decode_fields(
+    fields: &[Field],
+    buffer: &[u8],
+    offset: usize,
+) -> Result<Vec<(&'static str, Value)>, DecodeDynamicError> {
+    fields
+        .iter()
+        .map(|field| {
+            let buffer = &buffer[field.offset..field.offset + field.layout.size];
+            Ok((
+                field.name,
+                decode(field.layout, buffer, offset + field.offset)?,
+            ))
+        })
+        .collect()
+}
+
+fn decode_primitive(
+    primitive: Primitive,
+    endian: Endian,
+    buffer: &[u8],
+    offset: usize,
+) -> Result<Value, DecodeDynamicError> {
+    let bits = read(buffer, endian);
+    Ok(match primitive {
+        | Primitive::Bool => match bits {
+            | 0 => Value::Bool(false),
+            | 1 => Value::Bool(true),
+            | _ => return Err(DecodeDynamicError::IllegalBitPattern { offset }),
+        },
+        | Primitive::Char => match u32::try_from(bits).ok().and_then(char::from_u32) {
+            | Some(char) => Value::Char(char),
+            | None => return Err(DecodeDynamicError::CharTryFromError { offset }),
+        },
+        | Primitive::Float if buffer.len() == 4 => {
+            Value::F32(f32::from_bits(bits as u32))
+        }
+        | Primitive::Float => Value::F64(f64::from_bits(bits as u64)),
+        | Primitive::Unsigned => Value::Unsigned(bits),
+        | Primitive::Signed => Value::Signed(sign_extend(bits, buffer.len())),
+        | Primitive::NonZeroUnsigned | Primitive::NonZeroSigned if bits == 0 => {
+            return Err(DecodeDynamicError::IllegalBitPattern { offset });
+        }
+        | Primitive::NonZeroUnsigned => Value::Unsigned(bits),
+        | Primitive::NonZeroSigned => Value::Signed(sign_extend(bits, buffer.len())),
+    })
+}
+
+/// The variant selected by the tag at the start of `buffer`.
+fn variant_of(
+    tag: &Layout,
+    variants: &'static [Variant],
+    buffer: &[u8],
+) -> Option<&'static Variant> {
+    let Kind::Primitive { primitive, endian } = tag.kind else {
+        return None;
+    };
+    let bits = read(&buffer[..tag.size], endian);
+    let value = match primitive {
+        | Primitive::Signed | Primitive::NonZeroSigned => {
+            i64::try_from(sign_extend(bits, tag.size)).ok()?
+        }
+        | _ => i64::try_from(bits).ok()?,
+    };
+    variants.iter().find(|variant| variant.tag == value)
+}
+
+fn encode(
+    layout: &Layout,
+    value: &Value,
+    buffer: &mut [u8],
+    offset: usize,
+) -> Result<(), EncodeDynamicError> {
+    let mismatch = EncodeDynamicError::Mismatch { offset };
+    match (layout.kind, value) {
+        | (Kind::Primitive { primitive, endian }, value) => {
+            let bits = encode_primitive(primitive, value, buffer.len(), offset)?;
+            write(buffer, endian, bits);
+            Ok(())
+        }
+        | (Kind::Array { element, len }, Value::Array(values)) => {
+            if values.len() != len {
+                return Err(mismatch);
+            }
+            for (i, value) in values.iter().enumerate() {
+                let start = i * element.size;
+                let buffer = &mut buffer[start..start + element.size];
+                encode(element, value, buffer, offset + start)?;
+            }
+            Ok(())
+        }
+        | (Kind::Struct { fields }, Value::Tuple(values)) if is_tuple(fields) => {
+            if values.len() != fields.len() {
+                return Err(mismatch);
+            }
+            encode_fields(fields.iter().zip(values), buffer, offset)
+        }
+        | (Kind::Struct { fields }, Value::Struct(values)) => {
+            let names = fields.iter().map(|field| field.name);
+            if !names.eq(values.iter().map(|(name, _)| *name)) {
+                return Err(mismatch);
+            }
+            encode_fields(
+                fields.iter().zip(values.iter().map(|(_, value)| value)),
+                buffer,
+                offset,
+            )
+        }
+        | (Kind::Enum { tag, .. }, Value::Option(value)) => {
+            let Some(payload) = layout.option_payload() else {
+                return Err(mismatch);
+            };
+            encode(
+                tag,
+                &Value::Bool(value.is_some()),
+                &mut buffer[..tag.size],
+                offset,
+            )?;
+            match value {
+                | Some(value) => encode_fields([(payload, &**value)], buffer, offset),
+                | None => Ok(()),
+            }
+        }
+        | (Kind::Enum { tag, variants, .. }, Value::Enum { variant, fields }) => {
+            let variant = variants
+                .iter()
+                .find(|candidate| candidate.name == *variant)
+                .ok_or(mismatch)?;
+            let names = variant.fields.iter().map(|field| field.name);
+            if !names.eq(fields.iter().map(|(name, _)| *name)) {
+                return Err(mismatch);
+            }
+            let tag_value = match tag.kind {
+                | Kind::Primitive {
+                    primitive: Primitive::Bool,
+                    ..
+                } => Value::Bool(variant.tag != 0),
+                | Kind::Primitive {
+                    primitive: Primitive::Signed | Primitive::NonZeroSigned,
+                    ..
+                } => Value::Signed(variant.tag.into()),
+                | _ => Value::Unsigned(variant.tag as u128),
+            };
+            encode(tag, &tag_value, &mut buffer[..tag.size], offset)?;
+            encode_fields(
+                variant
+                    .fields
+                    .iter()
+                    .zip(fields.iter().map(|(_, value)| value)),
+                buffer,
+                offset,
+            )
+        }
+        | _ => Err(mismatch),
+    }
+}
+
+fn encode_fields<'a>(
+    fields: impl IntoIterator<Item = (&'a Field, &'a Value)>,
+    buffer: &mut [u8],
+    offset: usize,
+) -> Result<(), EncodeDynamicError> {
+    for (field, value) in fields {
+        let buffer = &mut buffer[field.offset..field.offset + field.layout.size];
+        encode(field.layout, value, buffer, offset + field.offset)?;
+    }
+    Ok(())
+}
+
+/// The bits of `value` as a primitive of `size` bytes.
+fn encode_primitive(
+    primitive: Primitive,
+    value: &Value,
+    size: usize,
+    offset: usize,
+) -> Result<u128, EncodeDynamicError> {
+    let out_of_range = EncodeDynamicError::OutOfRange { offset };
+    let bits = size as u32 * 8;
+    match (primitive, value) {
+        | (Primitive::Bool, Value::Bool(value)) => Ok(*value as u128),
+        | (Primitive::Char, Value::Char(value)) => Ok(*value as u128),
+        | (Primitive::Float, Value::F32(value)) if size == 4 => {
+            Ok(value.to_bits().into())
+        }
+        | (Primitive::Float, Value::F64(value)) if size == 8 => {
+            Ok(value.to_bits().into())
+        }
+        | (
+            Primitive::NonZeroUnsigned | Primitive::NonZeroSigned,
+            Value::Unsigned(0) | Value::Signed(0),
+        ) => Err(out_of_range),
+        | (Primitive::Unsigned | Primitive::NonZeroUnsigned, Value::Unsigned(value)) => {
+            match bits >= 128 || *value >> bits == 0 {
+                | true => Ok(*value),
+                | false => Err(out_of_range),
+            }
+        }
+        | (Primitive::Signed | Primitive::NonZeroSigned, Value::Signed(value)) => {
+            let bits = *value as u128;
+            match sign_extend(bits, size) == *value {
+                | true => Ok(bits),
+                | false => Err(out_of_range),
+            }
+        }
+        | _ => Err(EncodeDynamicError::Mismatch { offset }),
+    }
+}
+
+/// Whether `fields` are the positional fields `0`, `1`, … of a tuple.
+pub(crate) fn is_tuple(fields: &[Field]) -> bool {
+    fields
+        .iter()
+        .enumerate()
+        .all(|(i, field)| field.name.parse() == Ok(i))
+}
+
+/// Read an unsigned integer of up to 16 bytes.
+fn read(buffer: &[u8], endian: Endian) -> u128 {
+    let fold = |bits: u128, byte: &u8| bits << 8 | *byte as u128;
+    match endian {
+        | Endian::Little => buffer.iter().rev().fold(0, fold),
+        | Endian::Big => buffer.iter().fold(0, fold),
+    }
+}
+
+/// Write the low `buffer.len()` bytes of `bits`.
+fn write(buffer: &mut [u8], endian: Endian, bits: u128) {
+    let len = buffer.len();
+    for (i, byte) in buffer.iter_mut().enumerate() {
+        let shift = match endian {
+            | Endian::Little => i,
+            | Endian::Big => len - 1 - i,
+        };
+        *byte = (bits >> (shift * 8)) as u8;
+    }
+}
+
+/// Interpret the low `size` bytes of `bits` as a two's complement integer.
+fn sign_extend(bits: u128, size: usize) -> i128 {
+    let shift = 128 - size as u32 * 8;
+    (bits << shift) as i128 >> shift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_dynamic, encode_dynamic, DecodeDynamicError, EncodeDynamicError, Value,
+    };
+    use crate::key::tests::Rng;
+    use crate::schema::Schema;
+    use crate::{Deserialize, SerialSize, Serialize};
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use core::cmp::Ordering;
+    use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+    use core::num::{NonZeroI16, NonZeroU8};
+    use core::ops::{Bound, Range};
+
+    /// Check that the dynamic and the static implementations of `T` agree
+    /// on `samples` and on random corruptions of them:
+    /// both accept the same buffers, and encoding the decoded value
+    /// gives the same bytes as serializing the deserialized one.
+    fn assert_differential<T: Schema + Serialize + Deserialize>(samples: &[T], seed: u64)
+    where
+        [(); <T as SerialSize>::SIZE]:,
+    {
+        let mut rng = Rng(seed);
+        for sample in samples {
+            let bytes = sample.to_bytes();
+            for i in 0..256 {
+                let mut buffer = bytes;
+                // leave the first iteration uncorrupted
+                for _ in 0..(i % 4).min(buffer.len()) {
+                    let position = rng.next() as usize % buffer.len();
+                    buffer[position] = match rng.next() % 5 {
+                        | 0 => 0,
+                        | 1 => 1,
+                        | 2 => 2,
+                        | 3 => 0xff,
+                        | _ => rng.next() as u8,
+                    };
+                }
+
+                let value = decode_dynamic(&T::SCHEMA, &buffer);
+                let Ok(deserialized) = T::deserialize(&buffer) else {
+                    assert!(value.is_err(), "{buffer:?} decoded to {value:?}");
+                    continue;
+                };
+                let value = value.unwrap_or_else(|error| panic!("{buffer:?}: {error:?}"));
+                let mut encoded = [0xaa; <T as SerialSize>::SIZE];
+                encode_dynamic(&T::SCHEMA, &value, &mut encoded).unwrap();
+                assert_eq!(deserialized.to_bytes(), encoded, "{value:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_differential_primitives() {
+        assert_differential(&[(0u8, -1i16, 7u32, i64::MIN, u128::MAX, -3i128)], 1);
+        assert_differential(&[(true, 'a', '\u{10ffff}', 1.5f32, -0.0f64)], 2);
+        assert_differential(
+            &[(NonZeroU8::MIN, NonZeroI16::new(-300).unwrap(), [7u16; 3])],
+            3,
+        );
+    }
+
+    #[test]
+    fn test_differential_enums() {
+        assert_differential(&[None, Some((3u32, [Some(-1i8), None]))], 4);
+        assert_differential(&[Some(Some('x')), Some(None), None], 5);
+        assert_differential(
+            &[Bound::Included(5u16), Bound::Excluded(0), Bound::Unbounded],
+            6,
+        );
+        assert_differential(&[Ordering::Less, Ordering::Equal, Ordering::Greater], 7);
+        assert_differential(
+            &[
+                IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(Ipv6Addr::new(1, 2, 3, 4, 5, 6, 7, 8)),
+            ],
+            8,
+        );
+        assert_differential(&["[::1]:80".parse::<SocketAddr>().unwrap()], 9);
+        assert_differential(&[(Range { start: 1u8, end: 0 }, ())], 10);
+    }
+
+    #[test]
+    fn test_values() {
+        let bytes = (7u8, Some(-2i16), [true, false]).to_bytes();
+        let value = decode_dynamic(&<(u8, Option<i16>, [bool; 2])>::SCHEMA, &bytes);
+        assert_eq!(
+            Ok(Value::Tuple(vec![
+                Value::Unsigned(7),
+                Value::Option(Some(Box::new(Value::Signed(-2)))),
+                Value::Array(vec![Value::Bool(true), Value::Bool(false)]),
+            ])),
+            value
+        );
+
+        let bytes = Bound::Excluded('z').to_bytes();
+        assert_eq!(
+            Ok(Value::Enum {
+                variant: "Excluded",
+                fields: vec![("0", Value::Char('z'))],
+            }),
+            decode_dynamic(&<Bound<char>>::SCHEMA, &bytes)
+        );
+
+        let bytes = "1.2.3.4:5".parse::<SocketAddr>().unwrap().to_bytes();
+        let Ok(Value::Enum { variant, fields }) =
+            decode_dynamic(&SocketAddr::SCHEMA, &bytes)
+        else {
+            panic!("socket addresses are enums");
+        };
+        assert_eq!("V4", variant);
+        assert_eq!(
+            Value::Struct(vec![
+                (
+                    "ip",
+                    Value::Array(
+                        vec![1, 2, 3, 4].into_iter().map(Value::Unsigned).collect()
+                    )
+                ),
+                ("port", Value::Unsigned(5)),
+            ]),
+            fields[0].1
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let layout = <(u8, Option<char>, Ordering, IpAddr)>::SCHEMA;
+        let mut buffer = [0; 24];
+        assert_eq!(
+            Err(DecodeDynamicError::Length),
+            decode_dynamic(&layout, &buffer[..23])
+        );
+        assert!(decode_dynamic(&layout, &buffer).is_ok());
+
+        let errors = [
+            (1, 2, DecodeDynamicError::IllegalTag { offset: 1 }),
+            (6, 2, DecodeDynamicError::IllegalTag { offset: 6 }),
+            (12, 1, DecodeDynamicError::IllegalBitPattern { offset: 12 }),
+        ];
+        for (position, byte, error) in errors {
+            buffer[position] = byte;
+            assert_eq!(Err(error), decode_dynamic(&layout, &buffer));
+            buffer[position] = 0;
+        }
+
+        buffer[1] = 1;
+        buffer[2..6].copy_from_slice(&0xd800u32.to_bytes());
+        assert_eq!(
+            Err(DecodeDynamicError::CharTryFromError { offset: 2 }),
+            decode_dynamic(&layout, &buffer)
+        );
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let layout = <(u8, NonZeroU8, Bound<i8>)>::SCHEMA;
+        let encode = |values| encode_dynamic(&layout, &Value::Tuple(values), &mut [0; 4]);
+        let bound = Value::Enum {
+            variant: "Unbounded",
+            fields: vec![],
+        };
+        assert_eq!(
+            Ok(()),
+            encode(vec![
+                Value::Unsigned(255),
+                Value::Unsigned(1),
+                bound.clone()
+            ])
+        );
+        assert_eq!(
+            Err(EncodeDynamicError::OutOfRange { offset: 0 }),
+            encode(vec![
+                Value::Unsigned(256),
+                Value::Unsigned(1),
+                bound.clone()
+            ])
+        );
+        assert_eq!(
+            Err(EncodeDynamicError::OutOfRange { offset: 1 }),
+            encode(vec![Value::Unsigned(0), Value::Unsigned(0), bound.clone()])
+        );
+        assert_eq!(
+            Err(EncodeDynamicError::Mismatch { offset: 0 }),
+            encode(vec![Value::Signed(0), Value::Unsigned(1), bound])
+        );
+        assert_eq!(
+            Err(EncodeDynamicError::Mismatch { offset: 2 }),
+            encode(vec![
+                Value::Unsigned(0),
+                Value::Unsigned(1),
+                Value::Enum {
+                    variant: "Unknown",
+                    fields: vec![],
+                },
+            ])
+        );
+        assert_eq!(
+            Err(EncodeDynamicError::Length),
+            encode_dynamic(&layout, &Value::Tuple(vec![]), &mut [0; 3])
+        );
+    }
+}
@@ -0,0 +1,915 @@
+//! JSON descriptions of [`Layout`]s and [`Value`]s.
+//!
+//! Layouts are written as objects mirroring [`Layout`], [`Field`] and [`Variant`],
+//! with the [`Kind`] flattened into the layout, for example
+//! `{"name": "u16", "size": 2, "kind": "primitive", "primitive": "unsigned", "endian": "little"}`.
+//! They can be committed next to a program and read by tools without access to its types,
+//! such as the `arrise` binary.
+//!
+//! Values are written as follows:
+//! - integers and floats as numbers, with the strings `"NaN"`, `"inf"` and `"-inf"`
+//!   for the floats JSON has no numbers for
+//! - `bool`s as booleans and `char`s as strings of a single character
+//! - tuples and arrays as arrays, and other structs as objects
+//! - fieldless enum variants as their name, such as `"None"`,
+//!   and other variants as an object holding their fields under their name,
+//!   such as `{"Some": 1}`, `{"Pair": [1, 2]}` or `{"V4": {"ip": [127, 0, 0, 1], "port": 80}}`
+
+use crate::schema::dynamic::{is_tuple, Value};
+use crate::schema::{Endian, Field, Kind, Layout, Primitive, Variant};
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum JsonError {
+    /// Malformed JSON at byte `position`.
+    Syntax { position: usize },
+    /// Well-formed JSON that does not describe a layout, or a value of the layout,
+    /// at `path`, such as `$.fields[1].layout`.
+    Unexpected { path: String },
+}
+
+/// Describe `layout` as JSON.
+pub fn layout_to_json(layout: &Layout) -> String {
+    let mut output = String::new();
+    render(&layout_json(layout), 0, &mut output);
+    output.push('\n');
+    output
+}
+
+/// Read a layout described by [`layout_to_json`].
+///
+/// The layout is leaked to obtain the `'static` references of [`Layout`],
+/// which is meant for tools that read a few layouts at startup.
+/// The layout is checked to be consistent, so that it can be used with
+/// [`decode_dynamic`](crate::schema::dynamic::decode_dynamic).
+pub fn layout_from_json(json: &str) -> Result<&'static Layout, JsonError> {
+    let json = parse(json)?;
+    Ok(Box::leak(Box::new(parse_layout(&json, "$")?)))
+}
+
+/// Describe `value` as JSON.
+pub fn value_to_json(value: &Value) -> String {
+    let mut output = String::new();
+    render(&value_json(value), 0, &mut output);
+    output.push('\n');
+    output
+}
+
+/// Read a value of `layout` described by [`value_to_json`].
+///
+/// Integers are checked to fit their layout by
+/// [`encode_dynamic`](crate::schema::dynamic::encode_dynamic), not here.
+pub fn value_from_json(layout: &Layout, json: &str) -> Result<Value, JsonError> {
+    parse_value(layout, &parse(json)?, "$")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    /// A number as written, to be parsed according to the expected type.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            | Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            | _ => None,
+        }
+    }
+}
+
+fn string(value: &str) -> Json {
+    Json::String(value.to_owned())
+}
+
+fn number(value: impl ToString) -> Json {
+    Json::Number(value.to_string())
+}
+
+fn members<const LEN: usize>(members: [(&str, Json); LEN]) -> Vec<(String, Json)> {
+    members
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect()
+}
+
+fn object<const LEN: usize>(members: [(&str, Json); LEN]) -> Json {
+    Json::Object(self::members(members))
+}
+
+fn layout_json(layout: &Layout) -> Json {
+    let mut json =
+        members([("name", string(layout.name)), ("size", number(layout.size))]);
+    json.extend(match layout.kind {
+        | Kind::Primitive { primitive, endian } => members([
+            ("kind", string("primitive")),
+            ("primitive", string(primitive_name(primitive))),
+            (
+                "endian",
+                string(match endian {
+                    | Endian::Little => "little",
+                    | Endian::Big => "big",
+                }),
+            ),
+        ]),
+        | Kind::Array { element, len } => members([
+            ("kind", string("array")),
+            ("element", layout_json(element)),
+            ("len", number(len)),
+        ]),
+        | Kind::Struct { fields } => {
+            members([("kind", string("struct")), ("fields", fields_json(fields))])
+        }
+        | Kind::Enum {
+            tag,
+            variants,
+            zeroed_padding,
+        } => members([
+            ("kind", string("enum")),
+            ("tag", layout_json(tag)),
+            (
+                "variants",
+                Json::Array(
+                    variants
+                        .iter()
+                        .map(|variant| {
+                            object([
+                                ("name", string(variant.name)),
+                                ("tag", number(variant.tag)),
+                                ("fields", fields_json(variant.fields)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            ("zeroed_padding", Json::Bool(zeroed_padding)),
+        ]),
+    });
+    Json::Object(json)
+}
+
+fn fields_json(fields: &[Field]) -> Json {
+    Json::Array(
+        fields
+            .iter()
+            .map(|field| {
+                object([
+                    ("name", string(field.name)),
+                    ("offset", number(field.offset)),
+                    ("layout", layout_json(field.layout)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+const PRIMITIVES: [(Primitive, &str); 7] = [
+    (Primitive::Bool, "bool"),
+    (Primitive::Char, "char"),
+    (Primitive::Unsigned, "unsigned"),
+    (Primitive::Signed, "signed"),
+    (Primitive::Float, "float"),
+    (Primitive::NonZeroUnsigned, "nonzero_unsigned"),
+    (Primitive::NonZeroSigned, "nonzero_signed"),
+];
+
+fn primitive_name(primitive: Primitive) -> &'static str {
+    PRIMITIVES
+        .iter()
+        .find(|(candidate, _)| *candidate == primitive)
+        .map(|(_, name)| *name)
+        .unwrap()
+}
+
+fn value_json(value: &Value) -> Json {
+    match value {
+        | Value::Unsigned(value) => number(value),
+        | Value::Signed(value) => number(value),
+        | Value::F32(value) => float_json(*value as f64, value.to_string()),
+        | Value::F64(value) => float_json(*value, value.to_string()),
+        | Value::Bool(value) => Json::Bool(*value),
+        | Value::Char(value) => Json::String(value.to_string()),
+        | Value::Option(None) => string("None"),
+        | Value::Option(Some(value)) => object([("Some", value_json(value))]),
+        | Value::Tuple(values) | Value::Array(values) => {
+            Json::Array(values.iter().map(value_json).collect())
+        }
+        | Value::Struct(fields) => fields_value_json(fields),
+        | Value::Enum { variant, fields } if fields.is_empty() => string(variant),
+        | Value::Enum { variant, fields } => {
+            object([(*variant, fields_value_json(fields))])
+        }
+    }
+}
+
+/// Positional fields as an array, or the value of a single one, and named fields as an object.
+fn fields_value_json(fields: &[(&str, Value)]) -> Json {
+    match fields {
+        | [(name, value)] if *name == "0" => value_json(value),
+        | _ if fields
+            .iter()
+            .enumerate()
+            .all(|(i, (name, _))| name.parse() == Ok(i)) =>
+        {
+            Json::Array(fields.iter().map(|(_, value)| value_json(value)).collect())
+        }
+        | _ => Json::Object(
+            fields
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), value_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// `text` is the shortest representation of `value` that reads back to the same float.
+fn float_json(value: f64, text: String) -> Json {
+    match value {
+        | _ if value.is_nan() => string("NaN"),
+        | f64::INFINITY => string("inf"),
+        | f64::NEG_INFINITY => string("-inf"),
+        | _ => Json::Number(text),
+    }
+}
+
+/// Write `json`, breaking arrays and objects that contain arrays or objects across lines.
+fn render(json: &Json, indent: usize, output: &mut String) {
+    match json {
+        | Json::Null => output.push_str("null"),
+        | Json::Bool(value) => write!(output, "{value}").unwrap(),
+        | Json::Number(value) => output.push_str(value),
+        | Json::String(value) => render_string(value, output),
+        | Json::Array(values) => {
+            let multiline = nested(values.iter());
+            output.push('[');
+            for (i, value) in values.iter().enumerate() {
+                separate(i, multiline, indent + 1, output);
+                render(value, indent + 1, output);
+            }
+            close(!values.is_empty() && multiline, indent, output);
+            output.push(']');
+        }
+        | Json::Object(members) => {
+            let multiline = nested(members.iter().map(|(_, value)| value));
+            output.push('{');
+            for (i, (name, value)) in members.iter().enumerate() {
+                separate(i, multiline, indent + 1, output);
+                render_string(name, output);
+                output.push_str(": ");
+                render(value, indent + 1, output);
+            }
+            close(!members.is_empty() && multiline, indent, output);
+            output.push('}');
+        }
+    }
+}
+
+fn nested<'a>(mut values: impl Iterator<Item = &'a Json>) -> bool {
+    values.any(|value| matches!(value, Json::Array(_) | Json::Object(_)))
+}
+
+fn separate(i: usize, multiline: bool, indent: usize, output: &mut String) {
+    if i != 0 {
+        output.push(',');
+    }
+    match multiline {
+        | true => write!(output, "\n{:1$}", "", indent * 2).unwrap(),
+        | false if i != 0 => output.push(' '),
+        | false => {}
+    }
+}
+
+fn close(multiline: bool, indent: usize, output: &mut String) {
+    if multiline {
+        write!(output, "\n{:1$}", "", indent * 2).unwrap();
+    }
+}
+
+fn render_string(value: &str, output: &mut String) {
+    output.push('"');
+    for char in value.chars() {
+        match char {
+            | '"' => output.push_str("\\\""),
+            | '\\' => output.push_str("\\\\"),
+            | '\n' => output.push_str("\\n"),
+            | '\r' => output.push_str("\\r"),
+            | '\t' => output.push_str("\\t"),
+            | char if char.is_control() => {
+                write!(output, "\\u{:04x}", char as u32).unwrap()
+            }
+            | char => output.push(char),
+        }
+    }
+    output.push('"');
+}
+
+fn parse(json: &str) -> Result<Json, JsonError> {
+    let mut parser = Parser {
+        bytes: json.as_bytes(),
+        position: 0,
+    };
+    let value = parser.value()?;
+    parser.whitespace();
+    match parser.position == parser.bytes.len() {
+        | true => Ok(value),
+        | false => Err(parser.error()),
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> JsonError {
+        JsonError::Syntax {
+            position: self.position,
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.bytes.get(self.position) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), JsonError> {
+        match self.bytes[self.position..].starts_with(literal.as_bytes()) {
+            | true => {
+                self.position += literal.len();
+                Ok(())
+            }
+            | false => Err(self.error()),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.whitespace();
+        match self.bytes.get(self.position) {
+            | Some(b'n') => self.expect("null").map(|()| Json::Null),
+            | Some(b't') => self.expect("true").map(|()| Json::Bool(true)),
+            | Some(b'f') => self.expect("false").map(|()| Json::Bool(false)),
+            | Some(b'"') => self.string().map(Json::String),
+            | Some(b'-' | b'0'..=b'9') => self.number(),
+            | Some(b'[') => {
+                let mut values = Vec::new();
+                self.sequence(b']', |parser| {
+                    values.push(parser.value()?);
+                    Ok(())
+                })?;
+                Ok(Json::Array(values))
+            }
+            | Some(b'{') => {
+                let mut members = Vec::new();
+                self.sequence(b'}', |parser| {
+                    parser.whitespace();
+                    let name = parser.string()?;
+                    parser.whitespace();
+                    parser.expect(":")?;
+                    members.push((name, parser.value()?));
+                    Ok(())
+                })?;
+                Ok(Json::Object(members))
+            }
+            | _ => Err(self.error()),
+        }
+    }
+
+    /// Parse comma-separated elements with `element` up to `end`, after the opening bracket.
+    fn sequence(
+        &mut self,
+        end: u8,
+        mut element: impl FnMut(&mut Self) -> Result<(), JsonError>,
+    ) -> Result<(), JsonError> {
+        self.position += 1;
+        self.whitespace();
+        if self.bytes.get(self.position) == Some(&end) {
+            self.position += 1;
+            return Ok(());
+        }
+        loop {
+            element(self)?;
+            self.whitespace();
+            match self.bytes.get(self.position) {
+                | Some(b',') => self.position += 1,
+                | Some(byte) if *byte == end => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                | _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.position;
+        let digits = |parser: &mut Self| {
+            let start = parser.position;
+            while let Some(b'0'..=b'9') = parser.bytes.get(parser.position) {
+                parser.position += 1;
+            }
+            match parser.position == start {
+                | true => Err(parser.error()),
+                | false => Ok(()),
+            }
+        };
+        if self.bytes[self.position] == b'-' {
+            self.position += 1;
+        }
+        digits(self)?;
+        if self.bytes.get(self.position) == Some(&b'.') {
+            self.position += 1;
+            digits(self)?;
+        }
+        if let Some(b'e' | b'E') = self.bytes.get(self.position) {
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.bytes.get(self.position) {
+                self.position += 1;
+            }
+            digits(self)?;
+        }
+        let text = core::str::from_utf8(&self.bytes[start..self.position]).unwrap();
+        Ok(Json::Number(text.to_owned()))
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect("\"")?;
+        let mut output = Vec::new();
+        loop {
+            let Some(&byte) = self.bytes.get(self.position) else {
+                return Err(self.error());
+            };
+            self.position += 1;
+            match byte {
+                | b'"' => break,
+                | b'\\' => {
+                    let Some(&escape) = self.bytes.get(self.position) else {
+                        return Err(self.error());
+                    };
+                    self.position += 1;
+                    let char = match escape {
+                        | b'"' => '"',
+                        | b'\\' => '\\',
+                        | b'/' => '/',
+                        | b'b' => '\u{8}',
+                        | b'f' => '\u{c}',
+                        | b'n' => '\n',
+                        | b'r' => '\r',
+                        | b't' => '\t',
+                        | b'u' => self.unicode_escape()?,
+                        | _ => return Err(self.error()),
+                    };
+                    output.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                | byte if byte < 0x20 => return Err(self.error()),
+                | byte => output.push(byte),
+            }
+        }
+        // the input is a `str` and escapes are pushed as UTF-8
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    /// Parse the hex digits of a `\u` escape, and a second escape if they are a high surrogate.
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let high = self.hex4()?;
+        let code = match high {
+            | high if (0xd800..0xdc00).contains(&high) => {
+                self.expect("\\u")?;
+                let low = self.hex4()?;
+                if !(0xdc00..=0xdfff).contains(&low) {
+                    return Err(self.error());
+                }
+                0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+            }
+            | code => code,
+        };
+        char::from_u32(code).ok_or(self.error())
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let digits = self.bytes.get(self.position..self.position + 4);
+        let code = digits
+            .and_then(|digits| core::str::from_utf8(digits).ok())
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or(self.error())?;
+        self.position += 4;
+        Ok(code)
+    }
+}
+
+fn unexpected<T>(path: &str) -> Result<T, JsonError> {
+    Err(JsonError::Unexpected {
+        path: path.to_owned(),
+    })
+}
+
+fn member<'a>(
+    json: &'a Json,
+    path: &str,
+    key: &str,
+) -> Result<(&'a Json, String), JsonError> {
+    let path = format!("{path}.{key}");
+    match json.get(key) {
+        | Some(value) => Ok((value, path)),
+        | None => unexpected(&path),
+    }
+}
+
+fn parse_str(json: &Json, path: &str, key: &str) -> Result<&'static str, JsonError> {
+    match member(json, path, key)? {
+        | (Json::String(value), _) => Ok(value.clone().leak()),
+        | (_, path) => unexpected(&path),
+    }
+}
+
+fn parse_number<T: core::str::FromStr>(
+    json: &Json,
+    path: &str,
+    key: &str,
+) -> Result<T, JsonError> {
+    match member(json, path, key)? {
+        | (Json::Number(value), path) => value.parse().or_else(|_| unexpected(&path)),
+        | (_, path) => unexpected(&path),
+    }
+}
+
+fn parse_layout(json: &Json, path: &str) -> Result<Layout, JsonError> {
+    let name = parse_str(json, path, "name")?;
+    let size: usize = parse_number(json, path, "size")?;
+    let fits = |fields: &[Field], start: usize| {
+        fields.iter().all(|field| {
+            field.offset >= start
+                && field
+                    .offset
+                    .checked_add(field.layout.size)
+                    .is_some_and(|end| end <= size)
+        })
+    };
+    let kind = match parse_str(json, path, "kind")? {
+        | "primitive" => {
+            let name = parse_str(json, path, "primitive")?;
+            let primitive = PRIMITIVES
+                .iter()
+                .find(|(_, candidate)| *candidate == name)
+                .map(|(primitive, _)| *primitive);
+            let endian = match parse_str(json, path, "endian")? {
+                | "little" => Some(Endian::Little),
+                | "big" => Some(Endian::Big),
+                | _ => None,
+            };
+            let (Some(primitive), Some(endian)) = (primitive, endian) else {
+                return unexpected(path);
+            };
+            let valid_size = match primitive {
+                | Primitive::Bool => size == 1,
+                | Primitive::Char => size == 4,
+                | Primitive::Float => size == 4 || size == 8,
+                | _ => (1..=16).contains(&size),
+            };
+            if !valid_size {
+                return unexpected(&format!("{path}.size"));
+            }
+            Kind::Primitive { primitive, endian }
+        }
+        | "array" => {
+            let (element, element_path) = member(json, path, "element")?;
+            let element: &'static Layout =
+                Box::leak(Box::new(parse_layout(element, &element_path)?));
+            let len = parse_number(json, path, "len")?;
+            if element.size.checked_mul(len) != Some(size) {
+                return unexpected(&format!("{path}.size"));
+            }
+            Kind::Array { element, len }
+        }
+        | "struct" => {
+            let fields = parse_fields(json, path)?;
+            if !fits(fields, 0) {
+                return unexpected(&format!("{path}.fields"));
+            }
+            Kind::Struct { fields }
+        }
+        | "enum" => {
+            let (tag, tag_path) = member(json, path, "tag")?;
+            let tag: &'static Layout = Box::leak(Box::new(parse_layout(tag, &tag_path)?));
+            let valid_tag = matches!(
+                tag.kind,
+                Kind::Primitive {
+                    primitive: Primitive::Bool | Primitive::Unsigned | Primitive::Signed,
+                    ..
+                }
+            );
+            if !valid_tag || tag.size > size {
+                return unexpected(&tag_path);
+            }
+            let (Json::Array(variants), variants_path) = member(json, path, "variants")?
+            else {
+                return unexpected(&format!("{path}.variants"));
+            };
+            let variants = variants
+                .iter()
+                .enumerate()
+                .map(|(i, variant)| {
+                    let path = format!("{variants_path}[{i}]");
+                    let fields = parse_fields(variant, &path)?;
+                    if !fits(fields, tag.size) {
+                        return unexpected(&format!("{path}.fields"));
+                    }
+                    Ok(Variant {
+                        name: parse_str(variant, &path, "name")?,
+                        tag: parse_number(variant, &path, "tag")?,
+                        fields,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let zeroed_padding = match member(json, path, "zeroed_padding")? {
+                | (Json::Bool(value), _) => *value,
+                | (_, path) => return unexpected(&path),
+            };
+            Kind::Enum {
+                tag,
+                variants: variants.leak(),
+                zeroed_padding,
+            }
+        }
+        | _ => return unexpected(&format!("{path}.kind")),
+    };
+    Ok(Layout { name, size, kind })
+}
+
+fn parse_fields(json: &Json, path: &str) -> Result<&'static [Field], JsonError> {
+    let (Json::Array(fields), path) = member(json, path, "fields")? else {
+        return unexpected(&format!("{path}.fields"));
+    };
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let path = format!("{path}[{i}]");
+            let (layout, layout_path) = member(field, &path, "layout")?;
+            Ok(Field {
+                name: parse_str(field, &path, "name")?,
+                offset: parse_number(field, &path, "offset")?,
+                layout: Box::leak(Box::new(parse_layout(layout, &layout_path)?)),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fields.leak())
+}
+
+fn parse_value(layout: &Layout, json: &Json, path: &str) -> Result<Value, JsonError> {
+    match (layout.kind, json) {
+        | (Kind::Primitive { primitive, .. }, json) => {
+            parse_primitive(primitive, layout.size, json)
+                .map_or_else(|| unexpected(path), Ok)
+        }
+        | (Kind::Array { element, len }, Json::Array(values)) if values.len() == len => {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| parse_value(element, value, &format!("{path}[{i}]")))
+                .collect::<Result<_, _>>()
+                .map(Value::Array)
+        }
+        | (Kind::Struct { fields }, json) => {
+            let values = parse_fields_value(fields, json, path)?;
+            Ok(match is_tuple(fields) {
+                | true => {
+                    Value::Tuple(values.into_iter().map(|(_, value)| value).collect())
+                }
+                | false => Value::Struct(values),
+            })
+        }
+        | (Kind::Enum { variants, .. }, Json::String(name)) => {
+            let Some(variant) = variants
+                .iter()
+                .find(|variant| variant.name == name && variant.fields.is_empty())
+            else {
+                return unexpected(path);
+            };
+            Ok(match layout.option_payload() {
+                | Some(_) => Value::Option(None),
+                | None => Value::Enum {
+                    variant: variant.name,
+                    fields: Vec::new(),
+                },
+            })
+        }
+        | (Kind::Enum { variants, .. }, Json::Object(members)) if members.len() == 1 => {
+            let (name, json) = &members[0];
+            let Some(variant) = variants.iter().find(|variant| variant.name == name)
+            else {
+                return unexpected(path);
+            };
+            let mut fields =
+                parse_fields_value(variant.fields, json, &format!("{path}.{name}"))?;
+            Ok(match layout.option_payload() {
+                | Some(_) => {
+                    Value::Option(fields.pop().map(|(_, value)| Box::new(value)))
+                }
+                | None => Value::Enum {
+                    variant: variant.name,
+                    fields,
+                },
+            })
+        }
+        | _ => unexpected(path),
+    }
+}
+
+/// Parse the values of `fields` as written by [`fields_value_json`].
+fn parse_fields_value(
+    fields: &'static [Field],
+    json: &Json,
+    path: &str,
+) -> Result<Vec<(&'static str, Value)>, JsonError> {
+    let field_value = |field: &Field, json, path: String| {
+        Ok((field.name, parse_value(field.layout, json, &path)?))
+    };
+    match (fields, json) {
+        | ([field], json) if field.name == "0" => {
+            Ok(Vec::from([field_value(field, json, path.to_owned())?]))
+        }
+        | (fields, Json::Array(values))
+            if is_tuple(fields) && values.len() == fields.len() =>
+        {
+            fields
+                .iter()
+                .zip(values)
+                .enumerate()
+                .map(|(i, (field, json))| {
+                    field_value(field, json, format!("{path}[{i}]"))
+                })
+                .collect()
+        }
+        | (fields, Json::Object(members)) if members.len() == fields.len() => fields
+            .iter()
+            .map(|field| {
+                let (json, path) = member(json, path, field.name)?;
+                field_value(field, json, path)
+            })
+            .collect(),
+        | _ => unexpected(path),
+    }
+}
+
+fn parse_primitive(primitive: Primitive, size: usize, json: &Json) -> Option<Value> {
+    match (primitive, json) {
+        | (Primitive::Bool, Json::Bool(value)) => Some(Value::Bool(*value)),
+        | (Primitive::Char, Json::String(value)) => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                | (Some(char), None) => Some(Value::Char(char)),
+                | _ => None,
+            }
+        }
+        | (Primitive::Float, Json::Number(value) | Json::String(value)) => {
+            if matches!(json, Json::String(_))
+                && !["NaN", "inf", "-inf"].contains(&&**value)
+            {
+                return None;
+            }
+            match size {
+                | 4 => value.parse().ok().map(Value::F32),
+                | _ => value.parse().ok().map(Value::F64),
+            }
+        }
+        | (Primitive::Unsigned | Primitive::NonZeroUnsigned, Json::Number(value)) => {
+            value.parse().ok().map(Value::Unsigned)
+        }
+        | (Primitive::Signed | Primitive::NonZeroSigned, Json::Number(value)) => {
+            value.parse().ok().map(Value::Signed)
+        }
+        | _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        layout_from_json, layout_to_json, value_from_json, value_to_json, JsonError,
+    };
+    use crate::schema::dynamic::{decode_dynamic, Value};
+    use crate::schema::{Layout, Schema};
+    use crate::Serialize;
+    use alloc::borrow::ToOwned;
+    use alloc::boxed::Box;
+    use alloc::vec;
+    use core::net::{SocketAddr, SocketAddrV4};
+    use core::ops::Bound;
+    use core::time::Duration;
+
+    fn assert_layout_round_trip(layout: &Layout) {
+        assert_eq!(Ok(layout), layout_from_json(&layout_to_json(layout)));
+    }
+
+    #[test]
+    fn test_layout() {
+        let json = layout_to_json(&SocketAddrV4::SCHEMA);
+        assert!(json.starts_with(
+            "{\n  \"name\": \"SocketAddrV4\",\n  \"size\": 6,\n  \"kind\": \"struct\","
+        ));
+        assert!(json.contains(
+            "\"layout\": {\"name\": \"u16\", \"size\": 2, \"kind\": \"primitive\", \
+             \"primitive\": \"unsigned\", \"endian\": \"big\"}\n"
+        ));
+        assert_layout_round_trip(&u8::SCHEMA);
+        assert_layout_round_trip(&<(bool, char, [f64; 2], ())>::SCHEMA);
+        assert_layout_round_trip(&<Option<Bound<i128>>>::SCHEMA);
+        assert_layout_round_trip(&<(Duration, SocketAddr)>::SCHEMA);
+    }
+
+    #[test]
+    fn test_value() {
+        type Message = (u8, Option<(char, f32)>, [i16; 2], Bound<u32>, SocketAddr);
+        let message: Message = (
+            7,
+            Some(('"', -1.5)),
+            [-1, 300],
+            Bound::Excluded(9),
+            "10.0.0.1:80".parse().unwrap(),
+        );
+        let value = decode_dynamic(&Message::SCHEMA, &message.to_bytes()).unwrap();
+        let json = value_to_json(&value);
+        assert_eq!(
+            "[\n  7,\n  {\n    \"Some\": [\"\\\"\", -1.5]\n  },\n  [-1, 300],\n  \
+             {\"Excluded\": 9},\n  {\n    \"V4\": {\n      \"ip\": [10, 0, 0, 1],\n      \
+             \"port\": 80\n    }\n  }\n]\n",
+            json
+        );
+        assert_eq!(Ok(value), value_from_json(&Message::SCHEMA, &json));
+
+        let layout = <(Option<Option<u8>>, f64, f32)>::SCHEMA;
+        let json = "[{\"Some\": \"None\"}, \"inf\", 1e3]";
+        let value = Value::Tuple(vec![
+            Value::Option(Some(Box::new(Value::Option(None)))),
+            Value::F64(f64::INFINITY),
+            Value::F32(1000.0),
+        ]);
+        assert_eq!(Ok(&value), value_from_json(&layout, json).as_ref());
+        assert_eq!(
+            "[\n  {\"Some\": \"None\"},\n  \"inf\",\n  1000\n]\n",
+            value_to_json(&value)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Err(JsonError::Syntax { position: 5 }),
+            value_from_json(&u8::SCHEMA, "[1, 2")
+        );
+        assert_eq!(
+            Err(JsonError::Syntax { position: 2 }),
+            value_from_json(&u8::SCHEMA, "1 2")
+        );
+
+        let unexpected = |path: &str| {
+            Some(JsonError::Unexpected {
+                path: path.to_owned(),
+            })
+        };
+        let layout = <(bool, Option<[u8; 2]>)>::SCHEMA;
+        assert_eq!(
+            unexpected("$[0]"),
+            value_from_json(&layout, "[1, \"None\"]").err()
+        );
+        assert_eq!(
+            unexpected("$[1].Some[1]"),
+            value_from_json(&layout, "[true, {\"Some\": [1, -1.5]}]").err()
+        );
+        assert_eq!(
+            unexpected("$[1]"),
+            value_from_json(&layout, "[true, \"Some\"]").err()
+        );
+
+        let json = layout_to_json(&<(u8, u16)>::SCHEMA);
+        assert!(layout_from_json(&json).is_ok());
+        assert_eq!(
+            unexpected("$.fields"),
+            layout_from_json(&json.replace("\"offset\": 1", "\"offset\": 2")).err()
+        );
+        assert_eq!(
+            unexpected("$.fields[1].layout.size"),
+            layout_from_json(&json.replace("\"size\": 2", "\"size\": 17")).err()
+        );
+        assert_eq!(
+            unexpected("$.kind"),
+            layout_from_json(&json.replace("\"struct\"", "\"union\"")).err()
+        );
+    }
+}
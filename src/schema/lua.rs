@@ -0,0 +1,702 @@
+//! Generate Wireshark dissectors in Lua from [`Layout`]s.
+//!
+//! The dissector adds a tree item for every struct, array and enum,
+//! and a field for every primitive, named after the path to it, such as `telemetry.1.Some.0`.
+//! Fields are decoded in their byte order, and enum tags are shown with the variant names.
+//!
+//! Values that [`Deserialize`](crate::Deserialize) would reject are flagged with expert info:
+//! `<protocol>.illegal` for bit patterns such as a `bool` above `1`, a zero `NonZero`
+//! or non-zero padding, `<protocol>.illegal_char` for invalid chars
+//! and `<protocol>.illegal_tag` for unknown tags.
+//!
+//! Composites are dissected by functions in the `dissectors` table, keyed by their path,
+//! so that the number of composites is not limited by the number of locals Lua allows.
+//!
+//! The generated code works with Lua 5.1 through 5.4.
+
+use crate::schema::{Endian, Field, Kind, Layout, Primitive, Variant};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Helpers shared by all generated dissectors.
+const HELPERS: &str = r#"-- The unsigned integer in `size` bytes at `offset`. Exact up to 2^53.
+local function read_uint(buffer, offset, size, little_endian)
+    local value = 0
+    for i = 0, size - 1 do
+        local index = i
+        if little_endian then
+            index = size - 1 - i
+        end
+        value = value * 256 + buffer(offset + index, 1):uint()
+    end
+    return value
+end
+
+-- The two's complement integer in `size` bytes at `offset`.
+local function read_int(buffer, offset, size, little_endian)
+    local value = read_uint(buffer, offset, size, little_endian)
+    if value >= 2 ^ (8 * size - 1) then
+        value = value - 2 ^ (8 * size)
+    end
+    return value
+end
+
+local function is_illegal_char(value)
+    return value >= 0x110000 or (value >= 0xD800 and value < 0xE000)
+end
+
+local function all_zero(buffer, offset, size)
+    for i = 0, size - 1 do
+        if buffer(offset + i, 1):uint() ~= 0 then
+            return false
+        end
+    end
+    return true
+end
+
+local function add(tree, field, buffer, offset, size, little_endian)
+    if little_endian then
+        return tree:add_le(field, buffer(offset, size))
+    end
+    return tree:add(field, buffer(offset, size))
+end
+"#;
+
+/// Generate a dissector for the protocol `name`, whose packets consist of a `layout`.
+///
+/// `name` must be a valid Lua identifier and is lowercased for field names.
+/// If `udp_port` is set, the dissector registers itself for that port.
+pub fn dissector(name: &str, layout: &Layout, udp_port: Option<u16>) -> String {
+    let abbrev = name.to_ascii_lowercase();
+    let mut generator = Generator {
+        fields: Vec::new(),
+        functions: String::new(),
+    };
+    let root = generator.dissect(&abbrev, name, layout, "offset", "    ");
+
+    let mut fields = String::new();
+    for (i, field) in generator.fields.iter().enumerate() {
+        writeln!(fields, "fields[{}] = {field}", i + 1).unwrap();
+    }
+    let register = match udp_port {
+        | Some(port) => {
+            format!("\nDissectorTable.get(\"udp.port\"):add({port}, proto)\n")
+        }
+        | None => String::new(),
+    };
+
+    format!(
+        "-- Generated by arrise from the layout of `{layout_name}`. Do not edit.\n\
+         local proto = Proto(\"{abbrev}\", \"{name}\")\n\
+         local SIZE = {size}\n\
+         \n\
+         local expert_illegal = ProtoExpert.new(\"{abbrev}.illegal\", \
+             \"Illegal bit pattern\", expert.group.MALFORMED, expert.severity.ERROR)\n\
+         local expert_illegal_char = ProtoExpert.new(\"{abbrev}.illegal_char\", \
+             \"Illegal char\", expert.group.MALFORMED, expert.severity.ERROR)\n\
+         local expert_illegal_tag = ProtoExpert.new(\"{abbrev}.illegal_tag\", \
+             \"Illegal tag\", expert.group.MALFORMED, expert.severity.ERROR)\n\
+         proto.experts = {{ expert_illegal, expert_illegal_char, expert_illegal_tag }}\n\
+         \n\
+         local fields = {{}}\n\
+         {fields}\
+         proto.fields = fields\n\
+         \n\
+         {HELPERS}\n\
+         local dissectors = {{}}\n\
+         \n\
+         {functions}\
+         function proto.dissector(buffer, pinfo, tree)\n    \
+             if buffer:len() < SIZE then\n        \
+                 return 0\n    \
+             end\n    \
+             pinfo.cols.protocol = proto.name\n    \
+             local offset = 0\n    \
+             tree = tree:add(proto, buffer(0, SIZE))\n\
+         {root}    \
+             return SIZE\n\
+         end\n\
+         {register}",
+        layout_name = layout.name,
+        size = layout.size,
+        functions = generator.functions,
+    )
+}
+
+struct Generator {
+    /// The `ProtoField` constructors of all fields.
+    fields: Vec<String>,
+    /// The definitions of the entries of `dissectors`.
+    functions: String,
+}
+
+impl Generator {
+    /// Statements dissecting `layout` at `offset` into `tree`, indented by `indent`.
+    /// `path` names the layout and any functions it requires.
+    fn dissect(
+        &mut self,
+        path: &str,
+        label: &str,
+        layout: &Layout,
+        offset: &str,
+        indent: &str,
+    ) -> String {
+        match layout.kind {
+            | Kind::Primitive { primitive, endian } => self.primitive(
+                path,
+                label,
+                layout.size,
+                primitive,
+                endian,
+                offset,
+                indent,
+            ),
+            | _ => {
+                let function = self.define(path, layout);
+                format!("{indent}{function}(buffer, tree, {offset}, \"{label}\")\n")
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn primitive(
+        &mut self,
+        path: &str,
+        label: &str,
+        size: usize,
+        primitive: Primitive,
+        endian: Endian,
+        offset: &str,
+        indent: &str,
+    ) -> String {
+        let field = self.field(path, label, size, primitive, None);
+        let little_endian = endian == Endian::Little && size > 1;
+        let add =
+            format!("add(tree, {field}, buffer, {offset}, {size}, {little_endian})");
+        let check = match primitive {
+            | Primitive::Bool => {
+                format!("buffer({offset}, 1):uint() > 1")
+            }
+            | Primitive::Char => {
+                format!("is_illegal_char(read_uint(buffer, {offset}, {size}, {little_endian}))")
+            }
+            | Primitive::NonZeroUnsigned | Primitive::NonZeroSigned => {
+                format!("all_zero(buffer, {offset}, {size})")
+            }
+            | Primitive::Unsigned | Primitive::Signed | Primitive::Float => {
+                return format!("{indent}{add}\n");
+            }
+        };
+        let expert = match primitive {
+            | Primitive::Char => "expert_illegal_char",
+            | _ => "expert_illegal",
+        };
+        // a block keeps the number of locals per function bounded
+        format!(
+            "{indent}do\n\
+             {indent}    local item = {add}\n\
+             {indent}    if {check} then\n\
+             {indent}        item:add_proto_expert_info({expert})\n\
+             {indent}    end\n\
+             {indent}end\n"
+        )
+    }
+
+    /// Declare a field, returning the expression referring to it.
+    fn field(
+        &mut self,
+        path: &str,
+        label: &str,
+        size: usize,
+        primitive: Primitive,
+        valuestring: Option<&str>,
+    ) -> String {
+        let (constructor, base) = match (primitive, size) {
+            | (Primitive::Float, 4) => (String::from("float"), ""),
+            | (Primitive::Float, _) => (String::from("double"), ""),
+            | (_, 16) => (String::from("bytes"), ""),
+            | (Primitive::Char, _) => (format!("uint{}", size * 8), ", base.HEX"),
+            | (Primitive::Signed | Primitive::NonZeroSigned, _) => {
+                (format!("int{}", size * 8), ", base.DEC")
+            }
+            | (_, _) => (format!("uint{}", size * 8), ", base.DEC"),
+        };
+        let valuestring = match (primitive, valuestring) {
+            | (_, Some(valuestring)) => format!(", {valuestring}"),
+            | (Primitive::Bool, None) => {
+                String::from(", { [0] = \"false\", [1] = \"true\" }")
+            }
+            | (_, None) => String::new(),
+        };
+        self.fields.push(format!(
+            "ProtoField.{constructor}(\"{path}\", \"{label}\"{base}{valuestring})"
+        ));
+        format!("fields[{}]", self.fields.len())
+    }
+
+    /// Define a function dissecting a composite `layout`,
+    /// returning the expression referring to it.
+    fn define(&mut self, path: &str, layout: &Layout) -> String {
+        let function = format!("dissectors[\"{path}\"]");
+        let mut body = format!(
+            "    tree = tree:add(buffer(offset, {}), label)\n",
+            layout.size
+        );
+        match layout.kind {
+            | Kind::Primitive { .. } => unreachable!("primitives are dissected inline"),
+            | Kind::Array { element, len } => {
+                let element = self.dissect(
+                    &format!("{path}.element"),
+                    "[]",
+                    element,
+                    &format!("offset + i * {}", element.size),
+                    "        ",
+                );
+                write!(
+                    body,
+                    "    for i = 0, {} do\n{element}    end\n",
+                    len as isize - 1
+                )
+                .unwrap();
+            }
+            | Kind::Struct { fields } => {
+                body.push_str(&self.fields(path, fields, "    "))
+            }
+            | Kind::Enum {
+                tag,
+                variants,
+                zeroed_padding,
+            } => {
+                body.push_str(&self.variants(path, layout, tag, variants, zeroed_padding))
+            }
+        }
+
+        write!(
+            self.functions,
+            "{function} = function(buffer, tree, offset, label)\n{body}end\n\n"
+        )
+        .unwrap();
+        function
+    }
+
+    fn fields(&mut self, path: &str, fields: &[Field], indent: &str) -> String {
+        let mut statements = String::new();
+        for field in fields.iter().filter(|field| field.layout.size != 0) {
+            statements.push_str(&self.dissect(
+                &format!("{path}.{}", field.name),
+                field.name,
+                field.layout,
+                &format!("offset + {}", field.offset),
+                indent,
+            ));
+        }
+        statements
+    }
+
+    fn variants(
+        &mut self,
+        path: &str,
+        layout: &Layout,
+        tag: &Layout,
+        variants: &[Variant],
+        zeroed_padding: bool,
+    ) -> String {
+        let Kind::Primitive { primitive, endian } = tag.kind else {
+            unreachable!("tags are primitives");
+        };
+        let signed = matches!(primitive, Primitive::Signed | Primitive::NonZeroSigned);
+        let little_endian = endian == Endian::Little && tag.size > 1;
+
+        let mut valuestring = String::from("{");
+        for variant in variants {
+            write!(valuestring, " [{}] = \"{}\",", variant.tag, variant.name).unwrap();
+        }
+        valuestring.push_str(" }");
+        let field_primitive = match signed {
+            | true => Primitive::Signed,
+            | false => Primitive::Unsigned,
+        };
+        let field = self.field(
+            &format!("{path}.tag"),
+            "tag",
+            tag.size,
+            field_primitive,
+            Some(&valuestring),
+        );
+
+        let mut statements = format!(
+            "    local item = add(tree, {field}, buffer, offset, {size}, {little_endian})\n    \
+             local tag = read_{int}(buffer, offset, {size}, {little_endian})\n",
+            size = tag.size,
+            int = if signed { "int" } else { "uint" },
+        );
+        for (i, variant) in variants.iter().enumerate() {
+            let keyword = if i == 0 { "if" } else { "elseif" };
+            writeln!(statements, "    {keyword} tag == {} then", variant.tag).unwrap();
+            let end = variant
+                .fields
+                .iter()
+                .map(|field| field.offset + field.layout.size)
+                .max()
+                .unwrap_or(tag.size);
+            if zeroed_padding && end < layout.size {
+                write!(
+                    statements,
+                    "        if not all_zero(buffer, offset + {end}, {}) then\n            \
+                             tree:add(buffer(offset + {end}, {}), \"padding\")\
+                                 :add_proto_expert_info(expert_illegal)\n        \
+                         end\n",
+                    layout.size - end,
+                    layout.size - end,
+                )
+                .unwrap();
+            }
+            statements.push_str(&self.fields(
+                &format!("{path}.{}", variant.name),
+                variant.fields,
+                "        ",
+            ));
+        }
+        match variants.is_empty() {
+            | true => statements.push_str("    item:add_proto_expert_info(expert_illegal_tag)\n"),
+            | false => statements.push_str(
+                "    else\n        item:add_proto_expert_info(expert_illegal_tag)\n    end\n",
+            ),
+        }
+        statements
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dissector;
+    use crate::schema::Schema;
+
+    #[test]
+    fn test_dissector() {
+        let dissector = dissector(
+            "Message",
+            &<(u8, Option<i32>, [u16; 2])>::SCHEMA,
+            Some(4000),
+        );
+        assert!(dissector.contains("local proto = Proto(\"message\", \"Message\")\n"));
+        assert!(dissector.contains(
+            "ProtoField.uint8(\"message.1.tag\", \"tag\", base.DEC, { [0] = \"None\", [1] = \"Some\", })"
+        ));
+        assert!(dissector.contains(
+            "dissectors[\"message.2\"] = function(buffer, tree, offset, label)\n"
+        ));
+        assert!(dissector.contains(
+            "    dissectors[\"message.2\"](buffer, tree, offset + 6, \"2\")\n"
+        ));
+        assert!(dissector.contains("DissectorTable.get(\"udp.port\"):add(4000, proto)"));
+    }
+
+    /// A minimal stand-in for the Wireshark Lua API,
+    /// which prints the dissection tree instead of displaying it.
+    #[cfg(feature = "std")]
+    const WIRESHARK_STUB: &str = r#"
+base = { DEC = "DEC", HEX = "HEX" }
+expert = { group = { MALFORMED = "malformed" }, severity = { ERROR = "error" } }
+ProtoExpert = { new = function(abbrev) return { abbrev = abbrev } end }
+ProtoField = setmetatable({}, {
+    __index = function(_, type)
+        return function(abbrev, label, base, valuestring)
+            return { abbrev = abbrev, type = type, valuestring = valuestring }
+        end
+    end,
+})
+DissectorTable = { get = function() return { add = function() end } end }
+function Proto(name, description)
+    return { name = name, description = description }
+end
+
+function Buffer(hex)
+    local bytes = {}
+    for byte in hex:gmatch("%x%x") do
+        bytes[#bytes + 1] = tonumber(byte, 16)
+    end
+    local buffer = setmetatable({}, {
+        __call = function(_, offset, len)
+            assert(offset + len <= #bytes, "range out of bounds")
+            return {
+                offset = offset,
+                len = len,
+                value = function(self, little_endian)
+                    local value = 0
+                    for i = 0, len - 1 do
+                        local index = offset + i
+                        if little_endian then
+                            index = offset + len - 1 - i
+                        end
+                        value = value * 256 + bytes[index + 1]
+                    end
+                    return value
+                end,
+                uint = function(self)
+                    return self:value(false)
+                end,
+            }
+        end,
+    })
+    function buffer:len()
+        return #bytes
+    end
+    return buffer
+end
+
+function Tree(depth)
+    local tree = {}
+    local function add(self, item, range, little_endian)
+        local line = string.rep("  ", depth)
+        if type(range) == "string" then
+            line = line .. range .. " @" .. item.offset
+        elseif item.abbrev == nil then
+            line = line .. item.name .. " @" .. range.offset
+        else
+            local value = range:value(little_endian)
+            if item.type:match("^int") and value >= 2 ^ (8 * range.len - 1) then
+                value = value - 2 ^ (8 * range.len)
+            end
+            line = line .. item.abbrev .. " @" .. range.offset .. " = " .. string.format("%d", value)
+            if item.valuestring ~= nil and item.valuestring[value] ~= nil then
+                line = line .. " (" .. item.valuestring[value] .. ")"
+            end
+        end
+        print(line)
+        return Tree(depth + 1)
+    end
+    function tree:add(item, range)
+        return add(self, item, range, false)
+    end
+    function tree:add_le(item, range)
+        return add(self, item, range, true)
+    end
+    function tree:add_proto_expert_info(expert)
+        print(string.rep("  ", depth - 1) .. "! " .. expert.abbrev)
+    end
+    return tree
+end
+"#;
+
+    /// Run `script` with `lua`, returning its output.
+    #[cfg(feature = "std")]
+    fn run(script: &str, test: &str) -> String {
+        use std::process::Command;
+
+        let dir = std::env::temp_dir()
+            .join(format!("arrise-lua-{test}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("script.lua"), script).unwrap();
+        let output = Command::new("lua")
+            .arg("script.lua")
+            .current_dir(&dir)
+            .output();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let output = output.expect("failed to run `lua`");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    /// Dissect sample buffers with the generated dissector and a stub of the Wireshark API.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `lua`"]
+    fn test_samples() {
+        use crate::schema::Endian;
+        use crate::Serialize;
+        use core::net::{IpAddr, Ipv4Addr};
+        use core::num::NonZeroU16;
+        use std::fmt::Write;
+
+        type Message = (bool, Option<char>, IpAddr, [NonZeroU16; 2], i16);
+
+        let hex = |bytes: &[u8]| {
+            bytes.iter().fold(String::new(), |mut hex, byte| {
+                write!(hex, "{byte:02x}").unwrap();
+                hex
+            })
+        };
+        let valid = (
+            true,
+            Some('a'),
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            [NonZeroU16::new(0x102).unwrap(), NonZeroU16::MAX],
+            -2i16,
+        )
+            .to_bytes();
+        let mut invalid = valid;
+        invalid[0] = 2;
+        invalid[2..6].copy_from_slice(&0xd800u32.to_bytes());
+        invalid[11] = 1;
+        invalid[23..25].fill(0);
+        let mut illegal_tag = valid;
+        illegal_tag[1] = 2;
+        illegal_tag[6] = 7;
+
+        let mut script = format!(
+            "{WIRESHARK_STUB}\n{}\n",
+            dissector("Message", &Message::SCHEMA, None)
+        );
+        for bytes in [valid, invalid, illegal_tag] {
+            writeln!(
+                script,
+                "print(\"--\")\n\
+                 proto.dissector(Buffer(\"{}\"), {{ cols = {{}} }}, Tree(0))",
+                hex(&bytes)
+            )
+            .unwrap();
+        }
+
+        let output = run(&script, "samples");
+        let expected = [
+            "--",
+            "message @0",
+            "  Message @0",
+            "    message.0 @0 = 1 (true)",
+            "    1 @1",
+            "      message.1.tag @1 = 1 (Some)",
+            "      message.1.Some.0 @2 = 97",
+            "    2 @6",
+            "      message.2.tag @6 = 0 (V4)",
+            "      0 @7",
+            "        message.2.V4.0.element @7 = 10",
+            "        message.2.V4.0.element @8 = 0",
+            "        message.2.V4.0.element @9 = 0",
+            "        message.2.V4.0.element @10 = 1",
+            "    3 @23",
+            "      message.3.element @23 = 258",
+            "      message.3.element @25 = 65535",
+            "    message.4 @27 = -2",
+            "--",
+            "message @0",
+            "  Message @0",
+            "    message.0 @0 = 2",
+            "    ! message.illegal",
+            "    1 @1",
+            "      message.1.tag @1 = 1 (Some)",
+            "      message.1.Some.0 @2 = 55296",
+            "      ! message.illegal_char",
+            "    2 @6",
+            "      message.2.tag @6 = 0 (V4)",
+            "      padding @11",
+            "      ! message.illegal",
+            "      0 @7",
+            "        message.2.V4.0.element @7 = 10",
+            "        message.2.V4.0.element @8 = 0",
+            "        message.2.V4.0.element @9 = 0",
+            "        message.2.V4.0.element @10 = 1",
+            "    3 @23",
+            "      message.3.element @23 = 0",
+            "      ! message.illegal",
+            "      message.3.element @25 = 65535",
+            "    message.4 @27 = -2",
+            "--",
+            "message @0",
+            "  Message @0",
+            "    message.0 @0 = 1 (true)",
+            "    1 @1",
+            "      message.1.tag @1 = 2",
+            "      ! message.illegal_tag",
+            "    2 @6",
+            "      message.2.tag @6 = 7",
+            "      ! message.illegal_tag",
+            "    3 @23",
+            "      message.3.element @23 = 258",
+            "      message.3.element @25 = 65535",
+            "    message.4 @27 = -2",
+        ];
+        assert!(
+            output.lines().eq(expected),
+            "primitives are {:?}:\n{output}",
+            Endian::PRIMITIVE
+        );
+    }
+
+    /// More composites than Lua allows locals, with paths that only differ in `.` and `_`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `lua`"]
+    fn test_paths() {
+        use crate::schema::{Field, Layout};
+
+        type Leaf = (u8,);
+        type Level = (Leaf, Leaf, Leaf, Leaf, Leaf, Leaf);
+        type Many = (Level, Level, Level, Level, Level, Level);
+
+        static A_B: Layout = Layout::structure(
+            "A_B",
+            &[Field {
+                name: "x",
+                offset: 0,
+                layout: &u8::SCHEMA,
+            }],
+        );
+        static B: Layout = Layout::structure(
+            "B",
+            &[Field {
+                name: "y",
+                offset: 0,
+                layout: &u8::SCHEMA,
+            }],
+        );
+        static A: Layout = Layout::structure(
+            "A",
+            &[Field {
+                name: "b",
+                offset: 0,
+                layout: &B,
+            }],
+        );
+        // 1 + 6 * (1 + 6 * (1 + 6)) = 259 composites
+        static MANY: Layout = <(Many, Many, Many, Many, Many, Many)>::SCHEMA;
+        static MESSAGE: Layout = Layout::structure(
+            "Message",
+            &[
+                Field {
+                    name: "a_b",
+                    offset: 0,
+                    layout: &A_B,
+                },
+                Field {
+                    name: "a",
+                    offset: 1,
+                    layout: &A,
+                },
+                Field {
+                    name: "many",
+                    offset: 2,
+                    layout: &MANY,
+                },
+            ],
+        );
+
+        let script = format!(
+            "{WIRESHARK_STUB}\n{}\n\
+             proto.dissector(Buffer(\"0102{}\"), {{ cols = {{}} }}, Tree(0))\n",
+            dissector("Message", &MESSAGE, None),
+            "00".repeat(MANY.size),
+        );
+        let output = run(&script, "paths");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            [
+                "message @0",
+                "  Message @0",
+                "    a_b @0",
+                "      message.a_b.x @0 = 1",
+                "    a @1",
+                "      b @1",
+                "        message.a.b.y @1 = 2",
+            ],
+            &lines[..7]
+        );
+    }
+}
@@ -0,0 +1,697 @@
+//! Generate Python modules from [`Layout`]s.
+//!
+//! The generated module exposes `SIZE`, `decode(data) -> value` and `encode(value) -> bytes`.
+//! Values are represented as follows:
+//! - integers and floats as `int` and `float`, `bool` as `bool` and `char` as `str`
+//! - arrays as `list`s
+//! - structs as dataclasses named after the path to them,
+//!   such as `Message_header` for the `header` field of `Message`,
+//!   with fields like `0` of tuples renamed to `_0`
+//! - `Option`-like enums, i.e. a `bool` tag, a `None` variant and a `Some` variant
+//!   with a single field, as `None` or the value, unless the value is `Option`-like itself
+//! - other enums, including the outer one of `Option<Option<T>>`,
+//!   as a dataclass per variant, such as `Message_kind_V4`
+//!
+//! Names that would clash, like those of the field `a_b` and the nested field `a.b`,
+//! get a number appended, such as `Message_a_b_2`.
+//!
+//! `decode` rejects the same bit patterns as [`Deserialize`](crate::Deserialize)
+//! by raising a `DeserializeError` with one of the kinds `IllegalBitPattern`,
+//! `CharTryFromError` or `IllegalTag`, the path to the offending value and its byte offset.
+
+use crate::schema::{Endian, Field, Kind, Layout, Names, Primitive, Variant};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// Definitions shared by all generated modules.
+const PRELUDE: &str = r#"from __future__ import annotations
+
+import struct
+from dataclasses import dataclass
+from typing import Any
+
+
+class DeserializeError(ValueError):
+    """Bytes rejected by the Rust `Deserialize` implementation."""
+
+    def __init__(self, kind: str, path: str, offset: int) -> None:
+        super().__init__(f"{kind} in {path} at byte {offset}")
+        self.kind = kind
+        self.path = path
+        self.offset = offset
+
+
+class _Int128:
+    """A `struct.Struct` lookalike for 128-bit integers."""
+
+    size = 16
+
+    def __init__(self, byteorder: str, signed: bool) -> None:
+        self.byteorder = byteorder
+        self.signed = signed
+
+    def unpack_from(self, buf, offset: int) -> tuple:
+        data = bytes(buf[offset:offset + 16])
+        return (int.from_bytes(data, self.byteorder, signed=self.signed),)
+
+    def pack_into(self, buf, offset: int, value: int) -> None:
+        buf[offset:offset + 16] = value.to_bytes(16, self.byteorder, signed=self.signed)
+
+
+def _number(fmt, buf, offset: int, path: str):
+    return fmt.unpack_from(buf, offset)[0]
+
+
+def _nonzero(fmt, buf, offset: int, path: str) -> int:
+    value = fmt.unpack_from(buf, offset)[0]
+    if value == 0:
+        raise DeserializeError("IllegalBitPattern", path, offset)
+    return value
+
+
+def _bool(fmt, buf, offset: int, path: str) -> bool:
+    value = buf[offset]
+    if value > 1:
+        raise DeserializeError("IllegalBitPattern", path, offset)
+    return value == 1
+
+
+def _char(fmt, buf, offset: int, path: str) -> str:
+    value = fmt.unpack_from(buf, offset)[0]
+    if value >= 0x110000 or 0xD800 <= value < 0xE000:
+        raise DeserializeError("CharTryFromError", path, offset)
+    return chr(value)
+
+
+def _zeroed(buf, start: int, end: int, path: str) -> None:
+    for offset in range(start, end):
+        if buf[offset] != 0:
+            raise DeserializeError("IllegalBitPattern", path, offset)
+"#;
+
+/// Generate a Python module for the type `name` with the given `layout`.
+///
+/// `name` must be a valid Python identifier.
+pub fn module(name: &str, layout: &Layout) -> String {
+    let mut generator = Generator {
+        formats: Vec::new(),
+        definitions: String::new(),
+        names: Names::new(),
+    };
+    let name = &generator.unique(name, layout);
+    let decode = generator.decode(name, layout, "offset", "path");
+    let encode = generator.encode(name, layout, "value", "offset");
+
+    let mut formats = String::new();
+    for (name, definition) in &generator.formats {
+        writeln!(formats, "{name} = {definition}").unwrap();
+    }
+
+    format!(
+        "# Generated by arrise from the layout of `{}`. Do not edit.\n\
+         {PRELUDE}\n\
+         {formats}\n\
+         SIZE = {size}\n\
+         \n\
+         {definitions}\
+         def decode(data: bytes, path: str = \"{name}\") -> Any:\n    \
+             if len(data) != SIZE:\n        \
+                 raise ValueError(f\"expected {{SIZE}} bytes, got {{len(data)}}\")\n    \
+             buf = memoryview(data)\n    \
+             offset = 0\n    \
+             return {decode}\n\
+         \n\
+         \n\
+         def encode(value: Any) -> bytes:\n    \
+             buf = bytearray(SIZE)\n    \
+             offset = 0\n    \
+             {encode}\n    \
+             return bytes(buf)\n",
+        layout.name,
+        size = layout.size,
+        definitions = generator.definitions,
+    )
+}
+
+struct Generator {
+    /// Names and definitions of the `struct.Struct`s for primitives.
+    formats: Vec<(String, String)>,
+    definitions: String,
+    /// Names of the composites defined so far.
+    names: Names,
+}
+
+impl Generator {
+    /// The name to define a composite `layout` under, made unique among those defined.
+    fn unique(&mut self, name: &str, layout: &Layout) -> String {
+        match layout.kind {
+            | Kind::Primitive { .. } => String::from(name),
+            | _ => self.names.take(name),
+        }
+    }
+
+    /// An expression decoding `layout` at `offset` from `buf`, reporting errors at `path`.
+    /// Defines the functions and classes it requires under `name`.
+    fn decode(
+        &mut self,
+        name: &str,
+        layout: &Layout,
+        offset: &str,
+        path: &str,
+    ) -> String {
+        match layout.kind {
+            | Kind::Primitive { primitive, endian } => {
+                let format = self.format(primitive, endian, layout.size);
+                let function = match primitive {
+                    | Primitive::Bool => "_bool",
+                    | Primitive::Char => "_char",
+                    | Primitive::NonZeroUnsigned | Primitive::NonZeroSigned => "_nonzero",
+                    | Primitive::Unsigned | Primitive::Signed | Primitive::Float => {
+                        "_number"
+                    }
+                };
+                format!("{function}({format}, buf, {offset}, {path})")
+            }
+            | _ => {
+                self.define(name, layout);
+                format!("_decode_{name}(buf, {offset}, {path})")
+            }
+        }
+    }
+
+    /// A statement encoding `value` with `layout` into `buf` at `offset`.
+    fn encode(
+        &mut self,
+        name: &str,
+        layout: &Layout,
+        value: &str,
+        offset: &str,
+    ) -> String {
+        match layout.kind {
+            | Kind::Primitive { primitive, endian } => {
+                let format = self.format(primitive, endian, layout.size);
+                match primitive {
+                    | Primitive::Bool => format!("buf[{offset}] = 1 if {value} else 0"),
+                    | Primitive::Char => {
+                        format!("{format}.pack_into(buf, {offset}, ord({value}))")
+                    }
+                    | _ => format!("{format}.pack_into(buf, {offset}, {value})"),
+                }
+            }
+            | _ => format!("_encode_{name}({value}, buf, {offset})"),
+        }
+    }
+
+    /// Define `_decode_{name}`, `_encode_{name}` and the classes for a composite `layout`.
+    fn define(&mut self, name: &str, layout: &Layout) {
+        match layout.kind {
+            | Kind::Primitive { .. } => {}
+            | Kind::Array { element, len } => {
+                let element_name = self.unique(&format!("{name}_element"), element);
+                let size = element.size;
+                let decode = self.decode(
+                    &element_name,
+                    element,
+                    &format!("offset + i * {size}"),
+                    "f\"{path}[{i}]\"",
+                );
+                let encode = self.encode(
+                    &element_name,
+                    element,
+                    "element",
+                    &format!("offset + i * {size}"),
+                );
+                write!(
+                    self.definitions,
+                    "def _decode_{name}(buf, offset: int, path: str) -> list:\n    \
+                         return [{decode} for i in range({len})]\n\
+                     \n\
+                     \n\
+                     def _encode_{name}(value: list, buf, offset: int) -> None:\n    \
+                         if len(value) != {len}:\n        \
+                             raise ValueError(f\"expected {len} elements, got {{len(value)}}\")\n    \
+                         for i, element in enumerate(value):\n        \
+                             {encode}\n\
+                     \n\
+                     \n"
+                )
+                .unwrap();
+            }
+            | Kind::Struct { fields } => {
+                self.define_class(name, fields);
+                let (decode, mut encode) = self.fields(name, fields, "    ");
+                if encode.is_empty() {
+                    encode.push_str("    pass\n");
+                }
+                write!(
+                    self.definitions,
+                    "def _decode_{name}(buf, offset: int, path: str) -> {name}:\n    \
+                         return {name}({decode})\n\
+                     \n\
+                     \n\
+                     def _encode_{name}(value: {name}, buf, offset: int) -> None:\n\
+                     {encode}\
+                     \n\
+                     \n"
+                )
+                .unwrap();
+            }
+            | Kind::Enum {
+                tag,
+                variants,
+                zeroed_padding,
+            } => self.define_enum(name, layout, tag, variants, zeroed_padding),
+        }
+    }
+
+    fn define_class(&mut self, name: &str, fields: &[Field]) {
+        let mut body = String::new();
+        for field in fields {
+            writeln!(body, "    {}: Any", identifier(field.name)).unwrap();
+        }
+        if fields.is_empty() {
+            body.push_str("    pass\n");
+        }
+        write!(self.definitions, "@dataclass\nclass {name}:\n{body}\n\n").unwrap();
+    }
+
+    /// The keyword arguments decoding `fields`,
+    /// and the statements encoding them from `value`, indented by `indent`.
+    fn fields(&mut self, name: &str, fields: &[Field], indent: &str) -> (String, String) {
+        let mut decode = Vec::new();
+        let mut encode = String::new();
+        for field in fields {
+            let member = identifier(field.name);
+            let field_name = self.unique(&format!("{name}_{}", field.name), field.layout);
+            let offset = format!("offset + {}", field.offset);
+            let path = format!("path + \".{}\"", field.name);
+            let field_decode = self.decode(&field_name, field.layout, &offset, &path);
+            decode.push(format!("{member}={field_decode}"));
+            let field_encode = self.encode(
+                &field_name,
+                field.layout,
+                &format!("value.{member}"),
+                &offset,
+            );
+            writeln!(encode, "{indent}{field_encode}").unwrap();
+        }
+        (decode.join(", "), encode)
+    }
+
+    fn define_enum(
+        &mut self,
+        name: &str,
+        layout: &Layout,
+        tag: &Layout,
+        variants: &[Variant],
+        zeroed_padding: bool,
+    ) {
+        // the raw tag, which is matched against the variants instead of being validated
+        let tag_format = match tag.kind {
+            | Kind::Primitive { primitive, endian } => {
+                self.format(primitive, endian, tag.size)
+            }
+            | _ => unreachable!("tags are primitives"),
+        };
+        let tag_decode = format!("_number({tag_format}, buf, offset, path)");
+        // `None` would be ambiguous for a payload that may be `None` itself
+        let is_option = layout
+            .option_payload()
+            .is_some_and(|payload| payload.layout.option_payload().is_none());
+
+        let mut decode = String::new();
+        let mut encode = String::new();
+        for variant in variants {
+            let variant_name = format!("{name}_{}", variant.name);
+            let end = variant
+                .fields
+                .iter()
+                .map(|field| field.offset + field.layout.size)
+                .max()
+                .unwrap_or(tag.size);
+            let padding = match zeroed_padding && end < layout.size {
+                | true => format!(
+                    "        _zeroed(buf, offset + {end}, offset + {}, path)\n",
+                    layout.size
+                ),
+                | false => String::new(),
+            };
+            let tag_encode =
+                format!("{tag_format}.pack_into(buf, offset, {})", variant.tag);
+
+            if is_option {
+                let field = &variant.fields.first();
+                match field {
+                    | None => {
+                        write!(
+                            decode,
+                            "    if tag == {}:\n{padding}        return None\n",
+                            variant.tag
+                        )
+                        .unwrap();
+                        write!(encode, "    if value is None:\n        {tag_encode}\n        return\n")
+                            .unwrap();
+                    }
+                    | Some(field) => {
+                        let field_name = self.unique(
+                            &format!("{variant_name}_{}", field.name),
+                            field.layout,
+                        );
+                        let offset = format!("offset + {}", field.offset);
+                        let field_decode =
+                            self.decode(&field_name, field.layout, &offset, "path");
+                        let field_encode =
+                            self.encode(&field_name, field.layout, "value", &offset);
+                        write!(
+                            decode,
+                            "    if tag == {}:\n{padding}        return {field_decode}\n",
+                            variant.tag
+                        )
+                        .unwrap();
+                        write!(encode, "    {tag_encode}\n    {field_encode}\n").unwrap();
+                    }
+                }
+                continue;
+            }
+
+            let variant_name = self.names.take(&variant_name);
+            self.define_class(&variant_name, variant.fields);
+            let (fields_decode, fields_encode) =
+                self.fields(&variant_name, variant.fields, "        ");
+            write!(
+                decode,
+                "    if tag == {}:\n{padding}        return {variant_name}({fields_decode})\n",
+                variant.tag
+            )
+            .unwrap();
+            write!(
+                encode,
+                "    if isinstance(value, {variant_name}):\n        \
+                     {tag_encode}\n\
+                 {fields_encode}        \
+                     return\n"
+            )
+            .unwrap();
+        }
+        if !is_option {
+            encode.push_str(&format!(
+                "    raise TypeError(f\"expected a variant of {name}, got {{value!r}}\")\n"
+            ));
+        }
+
+        write!(
+            self.definitions,
+            "def _decode_{name}(buf, offset: int, path: str) -> Any:\n    \
+                 tag = {tag_decode}\n\
+             {decode}    \
+                 raise DeserializeError(\"IllegalTag\", path, offset)\n\
+             \n\
+             \n\
+             def _encode_{name}(value: Any, buf, offset: int) -> None:\n\
+             {encode}\
+             \n\
+             \n"
+        )
+        .unwrap();
+    }
+
+    /// The name of a `struct.Struct` for a primitive, defining it if necessary.
+    fn format(&mut self, primitive: Primitive, endian: Endian, size: usize) -> String {
+        let (order, byteorder) = match endian {
+            | Endian::Little => ('<', "little"),
+            | Endian::Big => ('>', "big"),
+        };
+        let signed = matches!(primitive, Primitive::Signed | Primitive::NonZeroSigned);
+        let (code, definition) = match (primitive, size) {
+            | (_, 16) => (
+                if signed { "i128" } else { "u128" },
+                format!(
+                    "_Int128(\"{byteorder}\", signed={})",
+                    if signed { "True" } else { "False" }
+                ),
+            ),
+            | (Primitive::Float, 4) => ("f32", format!("struct.Struct(\"{order}f\")")),
+            | (Primitive::Float, _) => ("f64", format!("struct.Struct(\"{order}d\")")),
+            | (_, size) => {
+                let (code, letter) = match (signed, size) {
+                    | (true, 1) => ("i8", 'b'),
+                    | (true, 2) => ("i16", 'h'),
+                    | (true, 4) => ("i32", 'i'),
+                    | (true, _) => ("i64", 'q'),
+                    | (false, 1) => ("u8", 'B'),
+                    | (false, 2) => ("u16", 'H'),
+                    | (false, 4) => ("u32", 'I'),
+                    | (false, _) => ("u64", 'Q'),
+                };
+                (code, format!("struct.Struct(\"{order}{letter}\")"))
+            }
+        };
+
+        let name = format!(
+            "_{}_{}",
+            code.to_ascii_uppercase(),
+            &byteorder[..1].to_ascii_uppercase()
+        );
+        if !self.formats.iter().any(|(existing, _)| *existing == name) {
+            self.formats.push((name.clone(), definition));
+        }
+        name
+    }
+}
+
+/// Turn a field name into a Python identifier, prefixing names like `0` with `_`.
+fn identifier(name: &str) -> String {
+    match name.starts_with(|c: char| c.is_ascii_digit()) {
+        | true => format!("_{name}"),
+        | false => String::from(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::module;
+    use crate::schema::Schema;
+
+    #[test]
+    fn test_module() {
+        let module = module("Message", &<(u8, Option<i32>, [u16; 2])>::SCHEMA);
+        assert!(module.contains("SIZE = 10\n"));
+        assert!(module.contains("@dataclass\nclass Message:\n    _0: Any\n    _1: Any\n"));
+        assert!(module
+            .contains("def _decode_Message_2(buf, offset: int, path: str) -> list:"));
+        assert!(module.contains("    if tag == 1:\n        return _number(_I32_"));
+    }
+
+    /// Decode golden vectors serialized by Rust with the generated module,
+    /// and check that encoding the result reproduces them.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `python3`"]
+    fn test_golden() {
+        use crate::impls::core::range::Ordered;
+        use crate::schema::Kind;
+        use crate::Serialize;
+        use core::cmp::Ordering;
+        use core::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+        use core::num::NonZeroU16;
+        use core::ops::{Bound, Range};
+        use core::time::Duration;
+        use std::fmt::Write;
+
+        type Message = (
+            bool,
+            Option<(char, [Option<u8>; 2])>,
+            Bound<i64>,
+            IpAddr,
+            Ordered<Range<u128>>,
+            (Duration, f64, NonZeroU16, Ordering, ()),
+            [Option<Option<u8>>; 2],
+        );
+
+        let values: [Message; 2] = [
+            (
+                true,
+                Some(('ß', [Some(7), None])),
+                Bound::Excluded(-3),
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+                Ordered(1..u128::MAX),
+                (
+                    Duration::new(5, 6),
+                    -0.5,
+                    NonZeroU16::new(300).unwrap(),
+                    Ordering::Less,
+                    (),
+                ),
+                [Some(None), None],
+            ),
+            (
+                false,
+                None,
+                Bound::Unbounded,
+                IpAddr::V6(Ipv6Addr::LOCALHOST),
+                Ordered(0..0),
+                (
+                    Duration::ZERO,
+                    1e300,
+                    NonZeroU16::MAX,
+                    Ordering::Greater,
+                    (),
+                ),
+                [Some(Some(9)), Some(None)],
+            ),
+        ];
+        let expected = [
+            "Message(_0=True, _1=Message_1_Some_0(_0='ß', _1=[7, None]), \
+             _2=Message_2_Excluded(_0=-3), _3=Message_3_V4(_0=[10, 0, 0, 1]), \
+             _4=Message_4(start=1, end=340282366920938463463374607431768211455), \
+             _5=Message_5(_0=Message_5_0(secs=5, nanos=6), _1=-0.5, _2=300, \
+             _3=Message_5_3_Less(), _4=Message_5_4()), \
+             _6=[Message_6_element_Some(_0=None), Message_6_element_None()])",
+            "Message(_0=False, _1=None, _2=Message_2_Unbounded(), \
+             _3=Message_3_V6(_0=[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]), \
+             _4=Message_4(start=0, end=0), \
+             _5=Message_5(_0=Message_5_0(secs=0, nanos=0), _1=1e+300, _2=65535, \
+             _3=Message_5_3_Greater(), _4=Message_5_4()), \
+             _6=[Message_6_element_Some(_0=9), Message_6_element_Some(_0=None)])",
+        ];
+
+        let hex = |bytes: &[u8]| {
+            bytes.iter().fold(String::new(), |mut hex, byte| {
+                write!(hex, "{byte:02x}").unwrap();
+                hex
+            })
+        };
+        let mut script = String::from("import message\n");
+        for value in &values {
+            writeln!(
+                script,
+                "data = bytes.fromhex('{}')\n\
+                 value = message.decode(data)\n\
+                 assert message.encode(value) == data, value\n\
+                 print(repr(value))",
+                hex(&value.to_bytes())
+            )
+            .unwrap();
+        }
+
+        // illegal bit patterns: the bytes written at an offset and the expected error kind
+        let Kind::Struct { fields } = Message::SCHEMA.kind else {
+            panic!("tuples are structs");
+        };
+        let offset = |field: usize| fields[field].offset;
+        let surrogate = 0xd800u32.to_bytes();
+        let invalid: [(usize, &[u8], &str); 8] = [
+            (offset(0), &[2], "IllegalBitPattern"),
+            (offset(1), &[2], "IllegalTag"),
+            (offset(1) + 1, &surrogate, "CharTryFromError"),
+            (offset(2), &[3], "IllegalTag"),
+            // the padding after an IPv4 address
+            (offset(3) + 5, &[1], "IllegalBitPattern"),
+            (offset(5) + 20, &[0, 0], "IllegalBitPattern"),
+            (offset(5) + 22, &[2], "IllegalTag"),
+            (offset(6) + 1, &[2], "IllegalTag"),
+        ];
+        for (offset, patch, kind) in invalid {
+            let mut bytes = values[0].to_bytes();
+            bytes[offset..offset + patch.len()].copy_from_slice(patch);
+            writeln!(
+                script,
+                "try:\n    \
+                     message.decode(bytes.fromhex('{}'))\n    \
+                     raise AssertionError('accepted {kind}')\n\
+                 except message.DeserializeError as error:\n    \
+                     assert (error.kind, error.offset) == ('{kind}', {offset}), error",
+                hex(&bytes)
+            )
+            .unwrap();
+        }
+
+        let output = run(&module("Message", &Message::SCHEMA), &script, "golden");
+        assert!(output.lines().eq(expected));
+    }
+
+    /// Decode and encode a value whose paths only differ in `.` and `_`.
+    #[cfg(feature = "std")]
+    #[test]
+    #[ignore = "requires `python3`"]
+    fn test_names() {
+        use crate::schema::{Field, Layout};
+
+        static A_B: Layout = Layout::structure(
+            "A_B",
+            &[Field {
+                name: "x",
+                offset: 0,
+                layout: &u8::SCHEMA,
+            }],
+        );
+        static B: Layout = Layout::structure(
+            "B",
+            &[Field {
+                name: "y",
+                offset: 0,
+                layout: &u8::SCHEMA,
+            }],
+        );
+        static A: Layout = Layout::structure(
+            "A",
+            &[Field {
+                name: "b",
+                offset: 0,
+                layout: &B,
+            }],
+        );
+        static MESSAGE: Layout = Layout::structure(
+            "Message",
+            &[
+                Field {
+                    name: "a_b",
+                    offset: 0,
+                    layout: &A_B,
+                },
+                Field {
+                    name: "a",
+                    offset: 1,
+                    layout: &A,
+                },
+            ],
+        );
+
+        let script = "import message\n\
+                      value = message.decode(bytes([1, 2]))\n\
+                      assert message.encode(value) == bytes([1, 2]), value\n\
+                      print(repr(value))\n";
+        let output = run(&module("Message", &MESSAGE), script, "names");
+        assert_eq!(
+            "Message(a_b=Message_a_b(x=1), a=Message_a(b=Message_a_b_2(y=2)))\n",
+            output
+        );
+    }
+
+    /// Run `script` with `python3` next to `module` as `message.py`, returning its output.
+    #[cfg(feature = "std")]
+    fn run(module: &str, script: &str, test: &str) -> String {
+        use std::process::Command;
+
+        let dir = std::env::temp_dir()
+            .join(format!("arrise-python-{test}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("message.py"), module).unwrap();
+        std::fs::write(dir.join("script.py"), script).unwrap();
+        let output = Command::new("python3")
+            .arg("script.py")
+            .current_dir(&dir)
+            .output();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let output = output.expect("failed to run `python3`");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+}
@@ -0,0 +1,237 @@
+//! A typed buffer holding the serialized form of a value.
+
+use crate::{Deserialize, SerialSize, Serialize};
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A zero-sized marker for the minimum alignment of a [`Serialized`].
+pub trait Alignment: Copy + private::Sealed {}
+
+macro_rules! impl_alignment {
+    ($($name:ident => $align:literal),*) => {
+        $(
+        #[doc = concat!("Align a [`Serialized`] to ", stringify!($align), " bytes.")]
+        #[derive(Debug, Copy, Clone)]
+        #[repr(align($align))]
+        pub struct $name;
+
+        impl private::Sealed for $name {}
+        impl Alignment for $name {}
+        )*
+    };
+}
+
+impl_alignment!(
+    Align1 => 1,
+    Align2 => 2,
+    Align4 => 4,
+    Align8 => 8,
+    Align16 => 16,
+    Align32 => 32,
+    Align64 => 64,
+    Align128 => 128,
+    Align4096 => 4096
+);
+
+/// The serialized form of a `T`, aligned to at least `A`.
+///
+/// Comparisons and hashing operate on the serialized bytes.
+/// The bytes are only validated when the value is deserialized with [`get`](Self::get),
+/// unless the buffer was created with [`from_bytes`](Self::from_bytes).
+#[repr(C)]
+pub struct Serialized<T: SerialSize, A: Alignment = Align1>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    align: [A; 0],
+    bytes: [u8; <T as SerialSize>::SIZE],
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: SerialSize, A: Alignment> Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Wrap `bytes` without validating them.
+    pub const fn from_bytes_unchecked(bytes: [u8; <T as SerialSize>::SIZE]) -> Self {
+        Self {
+            align: [],
+            bytes,
+            _type: PhantomData,
+        }
+    }
+
+    /// The serialized bytes.
+    pub const fn as_bytes(&self) -> &[u8; <T as SerialSize>::SIZE] {
+        &self.bytes
+    }
+
+    /// Unwrap the serialized bytes.
+    pub const fn into_bytes(self) -> [u8; <T as SerialSize>::SIZE] {
+        self.bytes
+    }
+}
+
+impl<T: Serialize, A: Alignment> Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Serialize `value` into a new buffer.
+    pub fn from_value(value: &T) -> Self {
+        Self::from_bytes_unchecked(value.to_bytes())
+    }
+
+    /// Replace the contents of `self` with the serialized form of `value`.
+    pub fn set(&mut self, value: &T) {
+        value.serialize(&mut self.bytes)
+    }
+}
+
+impl<T: Deserialize, A: Alignment> Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    /// Wrap `bytes` if they hold a valid serialized `T`.
+    pub fn from_bytes(bytes: [u8; <T as SerialSize>::SIZE]) -> Result<Self, T::Error> {
+        T::validate(&bytes)?;
+        Ok(Self::from_bytes_unchecked(bytes))
+    }
+
+    /// Check whether `self` holds a valid serialized `T`, see [`Deserialize::validate`].
+    pub fn validate(&self) -> Result<(), T::Error> {
+        T::validate(&self.bytes)
+    }
+
+    /// Deserialize the contained `T`.
+    pub fn get(&self) -> Result<T, T::Error> {
+        T::deserialize(&self.bytes)
+    }
+}
+
+impl<T: SerialSize, A: Alignment> Clone for Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: SerialSize, A: Alignment> Copy for Serialized<T, A> where
+    [(); <T as SerialSize>::SIZE]:
+{
+}
+
+impl<T: SerialSize, A: Alignment> Debug for Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Serialized").field(&self.bytes).finish()
+    }
+}
+
+impl<T: SerialSize, A: Alignment> PartialEq for Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<T: SerialSize, A: Alignment> Eq for Serialized<T, A> where
+    [(); <T as SerialSize>::SIZE]:
+{
+}
+
+impl<T: SerialSize, A: Alignment> PartialOrd for Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: SerialSize, A: Alignment> Ord for Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+impl<T: SerialSize, A: Alignment> Hash for Serialized<T, A>
+where
+    [(); <T as SerialSize>::SIZE]:,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Align4096, Align64, Serialized};
+    use crate::impls::core::option::DeserializeOptionError;
+    use crate::impls::IllegalBitPattern;
+    use crate::Serialize;
+    use core::mem::{align_of, size_of};
+
+    #[test]
+    fn test_value() {
+        let mut serialized = Serialized::<(u16, bool)>::from_value(&(7, true));
+        assert_eq!(Ok((7, true)), serialized.get());
+
+        serialized.set(&(9, false));
+        assert_eq!(Ok((9, false)), serialized.get());
+        assert_eq!(&(9u16, false).to_bytes(), serialized.as_bytes());
+        assert_eq!(
+            serialized,
+            Serialized::from_bytes(serialized.into_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_eq!(
+            Err(IllegalBitPattern),
+            Serialized::<bool>::from_bytes([2]).map(drop)
+        );
+
+        let serialized = Serialized::<Option<u8>>::from_bytes_unchecked([3, 0]);
+        assert_eq!(
+            Err(DeserializeOptionError::IllegalTag),
+            serialized.validate()
+        );
+        assert_eq!(Err(DeserializeOptionError::IllegalTag), serialized.get());
+    }
+
+    #[test]
+    fn test_bytewise_ord() {
+        let a = Serialized::<[u8; 2]>::from_value(&[1, 0xff]);
+        let b = Serialized::<[u8; 2]>::from_value(&[2, 0]);
+        assert!(a < b);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_alignment() {
+        assert_eq!(1, align_of::<Serialized<[u8; 3]>>());
+        assert_eq!(3, size_of::<Serialized<[u8; 3]>>());
+        assert_eq!(64, align_of::<Serialized<u32, Align64>>());
+        assert_eq!(64, size_of::<Serialized<u32, Align64>>());
+        assert_eq!(4096, align_of::<Serialized<u8, Align4096>>());
+
+        let serialized = Serialized::<u32, Align64>::from_value(&5);
+        assert_eq!(0, serialized.as_bytes().as_ptr() as usize % 64);
+    }
+}